@@ -0,0 +1,390 @@
+//! `promptflow` 命令行工具：不启动 GUI，直接读写 prompt 库
+
+use crate::commands::file::{
+    data_dir_from_config, default_app_dir, export_archive, import_prompts, load_all_prompts, load_config_from,
+    prepare_data_dir, validate_data_dir, write_new_prompt,
+};
+use crate::commands::git::auto_commit_with;
+use crate::commands::revision::record_revision;
+use crate::commands::usage::{apply_usage_stats, log_usage};
+use crate::models::{AppConfig, CreatePromptInput, PromptItem, RenderedPrompt, UsageAction};
+use crate::utils::{missing_variables, prompt_matches, render_template, set_language, AppError, Language};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+const USAGE: &str = "\
+Usage: promptflow <command> [options]
+
+Commands:
+  list                         List prompts
+  show <id>                    Show a prompt
+  search <query>               Search titles, descriptions, tags and content
+  render <id> [--var k=v]...   Render a prompt with variables
+  add --title <title> [--content <text> | --file <path>] [--tag <tag>]... [--description <text>]
+                               Add a prompt (reads content from stdin when neither is given)
+  export [<dir>]               Export the library as a ZIP archive (default: current directory)
+  import <file.zip|file.md> [--overwrite]
+                               Import prompts from an export archive or a markdown file
+
+Options:
+  --folder <favorites|templates>  Limit to or write into a folder
+  --data-dir <path>               Use this library instead of the configured one
+  --json                          Print machine-readable JSON
+";
+
+#[derive(Default)]
+struct Options {
+    json: bool,
+    overwrite: bool,
+    data_dir: Option<PathBuf>,
+    folder: Option<String>,
+    title: Option<String>,
+    content: Option<String>,
+    file: Option<PathBuf>,
+    description: Option<String>,
+    tags: Vec<String>,
+    variables: HashMap<String, String>,
+    positional: Vec<String>,
+}
+
+struct Library {
+    data_dir: PathBuf,
+    config: AppConfig,
+}
+
+#[derive(Serialize)]
+struct ExportOutput {
+    path: String,
+}
+
+fn usage_error(message: impl Into<String>) -> AppError {
+    AppError::new(message, "USAGE")
+}
+
+/// AppError.code 到进程退出码的映射，脚本可以据此区分失败原因
+fn exit_code(code: &str) -> i32 {
+    match code {
+        "USAGE" => 2,
+        "NOT_FOUND" => 3,
+        "VALIDATION_ERROR" => 4,
+        "PARSE_ERROR" => 5,
+        "IO_ERROR" => 6,
+        "GIT_ERROR" => 7,
+        _ => 1,
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, AppError> {
+    let mut options = Options::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| usage_error(format!("{} requires a value", name)))
+        };
+
+        match arg.as_str() {
+            "--json" => options.json = true,
+            "--overwrite" => options.overwrite = true,
+            "--data-dir" => options.data_dir = Some(PathBuf::from(value(arg)?)),
+            "--folder" => options.folder = Some(value(arg)?),
+            "--title" => options.title = Some(value(arg)?),
+            "--content" => options.content = Some(value(arg)?),
+            "--file" => options.file = Some(PathBuf::from(value(arg)?)),
+            "--description" => options.description = Some(value(arg)?),
+            "--tag" => options.tags.push(value(arg)?),
+            "--var" => {
+                let pair = value(arg)?;
+                let (key, val) = pair
+                    .split_once('=')
+                    .ok_or_else(|| usage_error(format!("--var expects key=value, got {}", pair)))?;
+                options.variables.insert(key.trim().to_string(), val.to_string());
+            }
+            flag if flag.starts_with("--") => return Err(usage_error(format!("Unknown option: {}", flag))),
+            _ => options.positional.push(arg.clone()),
+        }
+    }
+
+    if let Some(ref folder) = options.folder {
+        if folder != "favorites" && folder != "templates" {
+            return Err(usage_error(format!("Unknown folder: {}", folder)));
+        }
+    }
+
+    Ok(options)
+}
+
+/// 只有写入 prompt 库的命令才会初始化数据目录（创建目录、安装默认模板包）
+fn open_library(options: &Options, writable: bool) -> Result<Library, AppError> {
+    // 指定 --data-dir 时不要求存在应用目录，配置缺失则使用默认值
+    let (config, data_dir) = match options.data_dir {
+        Some(ref dir) => {
+            let config = match default_app_dir() {
+                Ok(app_dir) => load_config_from(&app_dir.join("config.json"))?,
+                Err(_) => AppConfig::default(),
+            };
+            (config, dir.clone())
+        }
+        None => {
+            let app_dir = default_app_dir()?;
+            let config = load_config_from(&app_dir.join("config.json"))?;
+            let data_dir = data_dir_from_config(&config, app_dir)?;
+            (config, data_dir)
+        }
+    };
+    set_language(Language::from_tag(&config.ui.language));
+
+    let data_dir = if writable {
+        prepare_data_dir(data_dir, &config.history)?
+    } else {
+        validate_data_dir(&data_dir)?;
+        data_dir
+    };
+    Ok(Library { data_dir, config })
+}
+
+fn load_prompts(library: &Library, options: &Options) -> Result<Vec<PromptItem>, AppError> {
+    let mut prompts: Vec<PromptItem> = load_all_prompts(&library.data_dir, false)?
+        .into_iter()
+        .filter(|p| options.folder.as_ref().map(|f| *f == p.folder).unwrap_or(true))
+        .collect();
+    apply_usage_stats(&library.data_dir, &mut prompts);
+    prompts.sort_by(|a, b| a.folder.cmp(&b.folder).then_with(|| a.title.cmp(&b.title)));
+    Ok(prompts)
+}
+
+fn find_prompt(library: &Library, options: &Options) -> Result<PromptItem, AppError> {
+    let id = options
+        .positional
+        .first()
+        .ok_or_else(|| usage_error("Missing prompt id"))?;
+
+    load_prompts(library, options)?
+        .into_iter()
+        .find(|p| p.id == *id)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "prompt_not_found", &[&id]))
+}
+
+// 输出被管道提前关闭（如 `| head`）时不应 panic
+fn print(text: &str) {
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(text.as_bytes());
+    if !text.ends_with('\n') {
+        let _ = stdout.write_all(b"\n");
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), AppError> {
+    print(&serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_prompt_list(prompts: &[PromptItem], options: &Options) -> Result<(), AppError> {
+    if options.json {
+        return print_json(&prompts);
+    }
+
+    let lines: Vec<String> = prompts
+        .iter()
+        .map(|p| format!("{}/{}\t{}", p.folder, p.id, p.title))
+        .collect();
+    if !lines.is_empty() {
+        print(&lines.join("\n"));
+    }
+    Ok(())
+}
+
+fn cmd_search(library: &Library, options: &Options) -> Result<(), AppError> {
+    let query = options.positional.join(" ");
+    if query.trim().is_empty() {
+        return Err(usage_error("Missing search query"));
+    }
+
+    let matches: Vec<PromptItem> = load_prompts(library, options)?
+        .into_iter()
+        .filter(|p| prompt_matches(p, &query))
+        .collect();
+
+    print_prompt_list(&matches, options)
+}
+
+fn cmd_show(library: &Library, options: &Options) -> Result<(), AppError> {
+    let prompt = find_prompt(library, options)?;
+    if options.json {
+        return print_json(&prompt);
+    }
+
+    let mut text = format!("{}\n", prompt.title);
+    if let Some(ref description) = prompt.description {
+        text.push_str(&format!("{}\n", description));
+    }
+    if !prompt.tags.is_empty() {
+        text.push_str(&format!("Tags: {}\n", prompt.tags.join(", ")));
+    }
+    text.push('\n');
+    text.push_str(&prompt.content);
+    print(&text);
+    Ok(())
+}
+
+fn cmd_render(library: &Library, options: &Options) -> Result<(), AppError> {
+    let prompt = find_prompt(library, options)?;
+    let content = render_template(&prompt.content, &options.variables);
+    log_usage(&library.data_dir, &prompt.id, &prompt.folder, UsageAction::Render);
+
+    if options.json {
+        return print_json(&RenderedPrompt {
+            missing_variables: missing_variables(&prompt.content, &options.variables),
+            id: prompt.id,
+            folder: prompt.folder,
+            content,
+        });
+    }
+
+    print(&content);
+    Ok(())
+}
+
+fn cmd_add(library: &Library, options: &Options) -> Result<(), AppError> {
+    let title = options
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| usage_error("--title is required"))?;
+
+    let content = match (&options.content, &options.file) {
+        (Some(content), _) => content.clone(),
+        (None, Some(file)) => fs::read_to_string(file)?,
+        (None, None) => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            content
+        }
+    };
+    if content.trim().is_empty() {
+        return Err(AppError::localized("VALIDATION_ERROR", "prompt_empty", &[]));
+    }
+
+    let folder = options.folder.clone().unwrap_or_else(|| "favorites".to_string());
+    let prompt = write_new_prompt(
+        &library.data_dir,
+        CreatePromptInput {
+            title: title.to_string(),
+            content,
+            tags: options.tags.clone(),
+            description: options.description.clone(),
+            folder,
+            hotkey: None,
+        },
+    )?;
+
+    if let Err(e) = record_revision(&library.data_dir, &prompt, &library.config.history) {
+        eprintln!("warning: failed to record revision: {}", e);
+    }
+    auto_commit_with(&library.config.git, &library.data_dir, &format!("Add prompt: {}", prompt.title));
+
+    if options.json {
+        return print_json(&prompt);
+    }
+    print(&format!("Created {}/{}", prompt.folder, prompt.id));
+    Ok(())
+}
+
+fn cmd_export(library: &Library, options: &Options) -> Result<(), AppError> {
+    let target = match options.positional.first() {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir()?,
+    };
+    let path = export_archive(&library.data_dir, &target)?.to_string_lossy().to_string();
+
+    if options.json {
+        return print_json(&ExportOutput { path });
+    }
+    print(&path);
+    Ok(())
+}
+
+fn cmd_import(library: &Library, options: &Options) -> Result<(), AppError> {
+    let source = options
+        .positional
+        .first()
+        .map(PathBuf::from)
+        .ok_or_else(|| usage_error("Missing file to import"))?;
+    if !source.is_file() {
+        return Err(AppError::localized("NOT_FOUND", "file_not_found", &[&source.display()]));
+    }
+
+    let report = import_prompts(&library.data_dir, &source, options.folder.as_deref(), options.overwrite)?;
+
+    for prompt in &report.imported {
+        if let Err(e) = record_revision(&library.data_dir, prompt, &library.config.history) {
+            eprintln!("warning: failed to record revision for {}: {}", prompt.id, e);
+        }
+    }
+    if !report.imported.is_empty() {
+        let message = format!("Import {} prompts", report.imported.len());
+        auto_commit_with(&library.config.git, &library.data_dir, &message);
+    }
+
+    if options.json {
+        return print_json(&report);
+    }
+
+    let mut text = format!("Imported {}, skipped {}", report.imported.len(), report.skipped.len());
+    for skipped in &report.skipped {
+        text.push_str(&format!("\n  skipped {}", skipped));
+    }
+    print(&text);
+    Ok(())
+}
+
+fn execute(args: &[String]) -> Result<(), AppError> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(usage_error("Missing command"));
+    };
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        print(USAGE);
+        return Ok(());
+    }
+
+    let options = parse_options(rest)?;
+    let run = match command.as_str() {
+        "list" => |library: &Library, options: &Options| print_prompt_list(&load_prompts(library, options)?, options),
+        "show" => cmd_show,
+        "search" => cmd_search,
+        "render" => cmd_render,
+        "add" => cmd_add,
+        "export" => cmd_export,
+        "import" => cmd_import,
+        other => return Err(usage_error(format!("Unknown command: {}", other))),
+    };
+
+    let library = open_library(&options, matches!(command.as_str(), "add" | "import"))?;
+    run(&library, &options)
+}
+
+/// 执行命令行参数（不含程序名），返回进程退出码
+pub fn run(args: Vec<String>) -> i32 {
+    let json = args.iter().any(|a| a == "--json");
+
+    match execute(&args) {
+        Ok(()) => 0,
+        Err(e) => {
+            if json {
+                let _ = serde_json::to_string(&e).map(|error| eprintln!("{}", error));
+            } else {
+                eprintln!("error: {}", e.message);
+                if e.code == "USAGE" {
+                    eprintln!("\n{}", USAGE);
+                }
+            }
+            exit_code(&e.code)
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::file::{check_prompt_ref, ensure_directories, load_config_sync};
+use super::vault::read_prompt_file;
+use crate::services::clipboard;
+use crate::services::window::hide_main_window;
+use crate::utils::{parse_markdown_prompt, render_template, AppError};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+fn render_prompt(
+    app_handle: &AppHandle,
+    id: &str,
+    folder: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, AppError> {
+    check_prompt_ref(folder, id)?;
+    let data_dir = ensure_directories(app_handle)?;
+    let file_path = data_dir.join(folder).join(format!("{}.md", id));
+    if !file_path.exists() {
+        return Err(AppError::localized("NOT_FOUND", "prompt_not_found", &[&id]));
+    }
+
+    let content = read_prompt_file(&file_path)?;
+    let prompt = parse_markdown_prompt(&content, &file_path.to_string_lossy(), folder)?;
+    Ok(render_template(&prompt.content, variables))
+}
+
+fn copy_and_maybe_hide(app_handle: &AppHandle, text: &str) -> Result<(), AppError> {
+    clipboard::write_text(app_handle, text)?;
+
+    let close_after_copy = load_config_sync(app_handle)
+        .map(|c| c.ui.close_after_copy)
+        .unwrap_or(true);
+    if close_after_copy {
+        hide_main_window(app_handle);
+    }
+    Ok(())
+}
+
+// 粘贴需要等待焦点切换和目标应用读取剪贴板，放到阻塞线程池执行，避免占用异步运行时的工作线程
+async fn paste_in_background(app_handle: &AppHandle, text: String) -> Result<(), String> {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || clipboard::paste_into_previous_app(&app_handle, &text))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn copy_to_clipboard(app_handle: AppHandle, text: String) -> Result<(), String> {
+    copy_and_maybe_hide(&app_handle, &text).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn paste_to_previous_app(app_handle: AppHandle, text: String) -> Result<(), String> {
+    paste_in_background(&app_handle, text).await
+}
+
+/// 渲染 prompt 后复制到剪贴板；`paste` 为 true 时直接粘贴到之前聚焦的应用
+#[tauri::command]
+pub async fn copy_prompt(
+    app_handle: AppHandle,
+    id: String,
+    folder: String,
+    variables: Option<HashMap<String, String>>,
+    paste: Option<bool>,
+) -> Result<String, String> {
+    let rendered = render_prompt(&app_handle, &id, &folder, &variables.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    if paste.unwrap_or(false) {
+        paste_in_background(&app_handle, rendered.clone()).await?;
+    } else {
+        copy_and_maybe_hide(&app_handle, &rendered).map_err(|e| e.to_string())?;
+    }
+
+    Ok(rendered)
+}
@@ -1,17 +1,24 @@
-use crate::models::AppConfig;
+use super::file::{ensure_directories, load_all_prompts};
+use crate::models::{AppConfig, ConfigSaveReport};
+use crate::services::appearance::apply_appearance;
+use crate::services::server::{apply_server_config, generate_server_token};
+use crate::services::shortcut::{self, HotkeyRegistrationFailed};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{current_language, set_language, AppError, Language};
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
 fn get_config_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
     let document_dir = tauri::path::BaseDirectory::Document;
     let data_dir = app_handle
         .path()
         .resolve("PromptFlow", document_dir)
-        .map_err(|e| format!("Failed to resolve data directory: {}", e))?;
+        .map_err(|e| AppError::localized("IO_ERROR", "resolve_dir_failed", &[&e]).to_string())?;
     
     if !data_dir.exists() {
-        fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| AppError::io_failed("create_dir_failed", data_dir.display(), e).to_string())?;
     }
     
     Ok(data_dir.join("config.json"))
@@ -24,44 +31,109 @@ pub async fn load_config(app_handle: AppHandle) -> Result<AppConfig, String> {
     if !config_path.exists() {
         let default_config = AppConfig::default();
         let json = serde_json::to_string_pretty(&default_config)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+            .map_err(|e| AppError::localized("PARSE_ERROR", "serialize_config_failed", &[&e]).to_string())?;
         fs::write(&config_path, json)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+            .map_err(|e| AppError::localized("IO_ERROR", "write_config_failed", &[&e]).to_string())?;
         return Ok(default_config);
     }
     
     let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
+        .map_err(|e| AppError::localized("IO_ERROR", "read_config_failed", &[&e]).to_string())?;
     
     let config: AppConfig = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse config: {}", e))?;
+        .map_err(|e| AppError::localized("PARSE_ERROR", "parse_config_failed", &[&e]).to_string())?;
     
     Ok(config)
 }
 
 #[tauri::command]
-pub async fn save_config(app_handle: AppHandle, config: AppConfig) -> Result<(), String> {
+pub async fn save_config(app_handle: AppHandle, mut config: AppConfig) -> Result<ConfigSaveReport, String> {
     let config_path = get_config_path(&app_handle)?;
+
+    // 热更新主快捷键；只在快捷键相对已保存的配置发生变化时才注册，
+    // 注册失败时保留旧快捷键，并把结构化错误通知给界面
+    let persisted_hotkey = load_config(app_handle.clone()).await.ok().map(|c| c.ui.hotkey);
+    let hotkey_error = if persisted_hotkey.as_deref() == Some(config.ui.hotkey.as_str()) {
+        None
+    } else {
+        apply_hotkey(&app_handle, &mut config)
+    };
+
+    if config.server.enabled && config.server.token.is_empty() {
+        config.server.token = generate_server_token().map_err(|e| e.to_string())?;
+    }
     
     let json = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        .map_err(|e| AppError::localized("PARSE_ERROR", "serialize_config_failed", &[&e]).to_string())?;
     
     fs::write(&config_path, json)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
-    
-    Ok(())
+        .map_err(|e| AppError::localized("IO_ERROR", "write_config_failed", &[&e]).to_string())?;
+
+    apply_language(&app_handle, &config.ui.language);
+    apply_appearance(&app_handle, &config.ui);
+    let server_error = apply_server_config(&app_handle, &config.server).err();
+
+    // 配置已经保存，这里不再返回错误
+    Ok(ConfigSaveReport {
+        warnings: hotkey_error.into_iter().chain(server_error).collect(),
+    })
+}
+
+/// 切换后端文案语言；语言变化时重建托盘菜单
+fn apply_language(app_handle: &AppHandle, tag: &str) {
+    let language = Language::from_tag(tag);
+    if language != current_language() {
+        set_language(language);
+        refresh_tray_menu(app_handle);
+    }
+}
+
+fn apply_hotkey(app_handle: &AppHandle, config: &mut AppConfig) -> Option<AppError> {
+    let previous = shortcut::current_main_hotkey(app_handle);
+    if previous.as_deref() == Some(config.ui.hotkey.as_str()) {
+        return None;
+    }
+
+    // 先检查是否与 prompt 快捷键冲突；否则主快捷键会顶替掉该 prompt 的快捷键
+    let prompts = ensure_directories(app_handle)
+        .and_then(|data_dir| load_all_prompts(&data_dir, true))
+        .unwrap_or_default();
+    let err = shortcut::validate_main_hotkey(&config.ui.hotkey, &prompts)
+        .and_then(|_| shortcut::update_main_hotkey(app_handle, &config.ui.hotkey))
+        .err()?;
+
+    let _ = app_handle.emit(
+        "hotkey-registration-failed",
+        HotkeyRegistrationFailed {
+            requested: config.ui.hotkey.clone(),
+            fallback: previous.clone(),
+            code: err.code.clone(),
+            key: err.key,
+            message: err.message.clone(),
+        },
+    );
+    if let Some(previous) = previous {
+        config.ui.hotkey = previous;
+    }
+
+    Some(err)
 }
 
 #[tauri::command]
 pub async fn reset_config(app_handle: AppHandle) -> Result<AppConfig, String> {
     let config_path = get_config_path(&app_handle)?;
-    let default_config = AppConfig::default();
+    let mut default_config = AppConfig::default();
+    let _ = apply_hotkey(&app_handle, &mut default_config);
     
     let json = serde_json::to_string_pretty(&default_config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        .map_err(|e| AppError::localized("PARSE_ERROR", "serialize_config_failed", &[&e]).to_string())?;
     
     fs::write(&config_path, json)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
+        .map_err(|e| AppError::localized("IO_ERROR", "write_config_failed", &[&e]).to_string())?;
+
+    apply_language(&app_handle, &default_config.ui.language);
+    apply_appearance(&app_handle, &default_config.ui);
+    let _ = apply_server_config(&app_handle, &default_config.server);
     
     Ok(default_config)
 }
@@ -76,5 +148,5 @@ pub async fn get_api_key(app_handle: AppHandle) -> Result<String, String> {
 pub async fn set_api_key(app_handle: AppHandle, api_key: String) -> Result<(), String> {
     let mut config = load_config(app_handle.clone()).await?;
     config.api.api_key = api_key;
-    save_config(app_handle, config).await
+    save_config(app_handle, config).await.map(|_| ())
 }
@@ -0,0 +1,248 @@
+use super::file::{check_prompt_ref, ensure_directories, load_all_prompts, load_config_sync, read_prompt, refresh_prompt_hotkeys};
+use super::git::auto_commit;
+use super::revision::record_revision;
+use super::trash::move_to_trash;
+use super::usage::{apply_usage_stats, reassign_usage};
+use super::vault::{commit_title, write_prompt_file, VAULT_FOLDER};
+use crate::models::{DuplicateCluster, DuplicateKind, DuplicateMember, PromptItem, PromptRef};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{
+    content_hash, generate_timestamp, minhash_signature, serialize_markdown_prompt, signature_similarity, AppError,
+};
+use std::path::Path;
+use tauri::AppHandle;
+
+const DEFAULT_THRESHOLD: f64 = 0.8;
+
+struct Fingerprint {
+    hash: u64,
+    signature: Vec<u64>,
+}
+
+impl Fingerprint {
+    fn similarity(&self, other: &Fingerprint) -> f64 {
+        if self.hash == other.hash {
+            1.0
+        } else {
+            signature_similarity(&self.signature, &other.signature)
+        }
+    }
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// 两两比较相似度并用并查集聚类；相似关系可传递，因此簇内最低相似度可能低于阈值
+fn cluster_duplicates(prompts: &[PromptItem], threshold: f64) -> Vec<DuplicateCluster> {
+    let prompts: Vec<&PromptItem> = prompts.iter().filter(|p| !p.content.trim().is_empty()).collect();
+    let fingerprints: Vec<Fingerprint> = prompts
+        .iter()
+        .map(|p| Fingerprint {
+            hash: content_hash(&p.content),
+            signature: minhash_signature(&p.content),
+        })
+        .collect();
+
+    let mut parent: Vec<usize> = (0..prompts.len()).collect();
+    for i in 0..prompts.len() {
+        for j in (i + 1)..prompts.len() {
+            if fingerprints[i].similarity(&fingerprints[j]) >= threshold {
+                let (a, b) = (find_root(&mut parent, i), find_root(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut group_of_root: Vec<Option<usize>> = vec![None; prompts.len()];
+    for i in 0..prompts.len() {
+        let root = find_root(&mut parent, i);
+        match group_of_root[root] {
+            Some(g) => groups[g].push(i),
+            None => {
+                group_of_root[root] = Some(groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = groups
+        .into_iter()
+        .filter(|g| g.len() > 1)
+        .map(|group| {
+            let mut similarity: f64 = 1.0;
+            for (n, &i) in group.iter().enumerate() {
+                for &j in &group[n + 1..] {
+                    similarity = similarity.min(fingerprints[i].similarity(&fingerprints[j]));
+                }
+            }
+            let kind = if group.iter().all(|&i| fingerprints[i].hash == fingerprints[group[0]].hash) {
+                DuplicateKind::Exact
+            } else {
+                DuplicateKind::Near
+            };
+
+            let mut members: Vec<DuplicateMember> = group
+                .iter()
+                .map(|&i| DuplicateMember {
+                    id: prompts[i].id.clone(),
+                    folder: prompts[i].folder.clone(),
+                    title: prompts[i].title.clone(),
+                    file_path: prompts[i].file_path.clone(),
+                    use_count: prompts[i].use_count,
+                })
+                .collect();
+            members.sort_by(|a, b| b.use_count.cmp(&a.use_count).then_with(|| a.title.cmp(&b.title)));
+
+            DuplicateCluster { kind, similarity, members }
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| {
+        (a.kind != DuplicateKind::Exact)
+            .cmp(&(b.kind != DuplicateKind::Exact))
+            .then_with(|| b.similarity.total_cmp(&a.similarity))
+    });
+    clusters
+}
+
+/// 合并到 `keep`：标签取并集、使用次数相加、最近使用取最新，其余文件移入回收站
+fn merge_into(data_dir: &Path, keep: &PromptRef, duplicates: &[PromptRef]) -> Result<PromptItem, AppError> {
+    if duplicates.is_empty() {
+        return Err(AppError::localized("VALIDATION_ERROR", "merge_nothing", &[]));
+    }
+    // 引用来自界面，必须限制在库内的 prompt 目录，避免改写或移走库外的文件
+    for prompt_ref in std::iter::once(keep).chain(duplicates) {
+        check_prompt_ref(&prompt_ref.folder, &prompt_ref.id)?;
+    }
+    if duplicates.iter().any(|d| d.id == keep.id && d.folder == keep.folder) {
+        return Err(AppError::localized("VALIDATION_ERROR", "merge_keep_conflict", &[]));
+    }
+    // 重复的条目会在保留文件已改写后才移入回收站失败
+    for (n, d) in duplicates.iter().enumerate() {
+        if duplicates[..n].iter().any(|o| o.id == d.id && o.folder == d.folder) {
+            return Err(AppError::localized(
+                "VALIDATION_ERROR",
+                "merge_duplicate_ref",
+                &[&format!("{}/{}", d.folder, d.id)],
+            ));
+        }
+    }
+    // 合并会把标签、描述等写入保留的文件，跨越保险库会让加密内容以明文落盘
+    if duplicates.iter().any(|d| (d.folder == VAULT_FOLDER) != (keep.folder == VAULT_FOLDER)) {
+        return Err(AppError::localized("VALIDATION_ERROR", "merge_vault_crossing", &[]));
+    }
+
+    let mut kept = read_prompt(data_dir, &keep.folder, &keep.id)?;
+    let others = duplicates
+        .iter()
+        .map(|d| read_prompt(data_dir, &d.folder, &d.id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for other in &others {
+        for tag in &other.tags {
+            if !kept.tags.contains(tag) {
+                kept.tags.push(tag.clone());
+            }
+        }
+        kept.use_count = kept.use_count.saturating_add(other.use_count);
+        if other.last_used > kept.last_used {
+            kept.last_used = other.last_used.clone();
+        }
+        if !other.created_at.is_empty() && (kept.created_at.is_empty() || other.created_at < kept.created_at) {
+            kept.created_at = other.created_at.clone();
+        }
+        if kept.description.is_none() {
+            kept.description = other.description.clone();
+        }
+        if kept.hotkey.is_none() {
+            kept.hotkey = other.hotkey.clone();
+        }
+    }
+    kept.updated_at = generate_timestamp();
+
+    write_prompt_file(Path::new(&kept.file_path), &serialize_markdown_prompt(&kept))?;
+    for other in &others {
+        move_to_trash(data_dir, Path::new(&other.file_path), Some(&other.folder), Some(&other.id), commit_title(other))?;
+    }
+
+    let merged: Vec<(String, String)> = others.iter().map(|o| (o.folder.clone(), o.id.clone())).collect();
+    reassign_usage(data_dir, &merged, &kept.folder, &kept.id)?;
+
+    Ok(kept)
+}
+
+#[tauri::command]
+pub async fn find_duplicate_prompts(
+    app_handle: AppHandle,
+    threshold: Option<f64>,
+) -> Result<Vec<DuplicateCluster>, String> {
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    if !(threshold > 0.0 && threshold <= 1.0) {
+        return Err(AppError::localized("VALIDATION_ERROR", "invalid_threshold", &[]).to_string());
+    }
+
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let mut prompts = load_all_prompts(&data_dir, true).map_err(|e| e.to_string())?;
+    apply_usage_stats(&data_dir, &mut prompts);
+    Ok(cluster_duplicates(&prompts, threshold))
+}
+
+#[tauri::command]
+pub async fn merge_duplicate_prompts(
+    app_handle: AppHandle,
+    keep: PromptRef,
+    duplicates: Vec<PromptRef>,
+) -> Result<PromptItem, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let mut merged = merge_into(&data_dir, &keep, &duplicates).map_err(|e| e.to_string())?;
+
+    let history_config = load_config_sync(&app_handle).map(|c| c.history).unwrap_or_default();
+    if let Err(e) = record_revision(&data_dir, &merged, &history_config) {
+        eprintln!("[PromptFlow] Failed to record revision for {}: {}", merged.id, e);
+    }
+    auto_commit(&app_handle, &data_dir, &format!("Merge duplicates into: {}", commit_title(&merged)));
+    refresh_prompt_hotkeys(&app_handle);
+    refresh_tray_menu(&app_handle);
+
+    apply_usage_stats(&data_dir, std::slice::from_mut(&mut merged));
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt_ref(folder: &str, id: &str) -> PromptRef {
+        PromptRef { folder: folder.to_string(), id: id.to_string() }
+    }
+
+    #[test]
+    fn refuses_merges_across_the_vault() {
+        let data_dir = std::env::temp_dir().join(format!("promptflow-merge-{}", std::process::id()));
+        for (keep, duplicate) in [("favorites", VAULT_FOLDER), (VAULT_FOLDER, "templates")] {
+            let err = merge_into(&data_dir, &prompt_ref(keep, "a"), &[prompt_ref(duplicate, "b")]).unwrap_err();
+            assert_eq!(err.key, Some("merge_vault_crossing"));
+        }
+    }
+
+    #[test]
+    fn rejects_unsafe_and_repeated_refs() {
+        let data_dir = std::env::temp_dir().join(format!("promptflow-merge-refs-{}", std::process::id()));
+        let keep = prompt_ref("favorites", "a");
+
+        let err = merge_into(&data_dir, &prompt_ref("../..", "a"), &[prompt_ref("favorites", "b")]).unwrap_err();
+        assert_eq!(err.key, Some("unknown_folder"));
+        let err = merge_into(&data_dir, &keep, &[prompt_ref("favorites", "../b")]).unwrap_err();
+        assert_eq!(err.key, Some("invalid_prompt_id"));
+
+        let twice = [prompt_ref("templates", "b"), prompt_ref("templates", "b")];
+        let err = merge_into(&data_dir, &keep, &twice).unwrap_err();
+        assert_eq!(err.key, Some("merge_duplicate_ref"));
+    }
+}
@@ -1,9 +1,24 @@
-use crate::models::{AppConfig, CreatePromptInput, FileInfo, PromptItem, UpdatePromptInput};
-use crate::utils::{generate_timestamp, parse_markdown_prompt, serialize_markdown_prompt, AppError};
+use super::git::auto_commit;
+use super::packs::install_default_packs;
+use super::repository::{ensure_writable, load_shared_layers, read_layered_prompt, tag_active_repository};
+use super::revision::record_revision;
+use super::trash::move_to_trash;
+use super::usage::{apply_usage_stats, record_usage_event};
+use super::vault::{commit_title, is_vault_unlocked, read_prompt_file, write_prompt_file, VAULT_FOLDER};
+use crate::models::{
+    AppConfig, CreatePromptInput, FileInfo, HistoryConfig, ImportReport, PromptItem, UpdatePromptInput, UsageAction,
+};
+use crate::services::shortcut::{sync_prompt_hotkeys, validate_prompt_hotkey};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{
+    current_language, generate_id, generate_timestamp, is_safe_file_name, parse_markdown_prompt,
+    serialize_markdown_prompt, write_atomic, AppError, Language,
+};
+use std::cmp::Ordering;
 use std::fs::{self, File};
 use std::io::{Read, Write};
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
@@ -13,7 +28,7 @@ fn get_config_path(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
     let data_dir = app_handle
         .path()
         .resolve("PromptFlow", document_dir)
-        .map_err(|e| AppError::io_error(format!("Failed to resolve config directory: {}", e)))?;
+        .map_err(|e| AppError::localized("IO_ERROR", "resolve_dir_failed", &[&e]))?;
     
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir)?;
@@ -22,26 +37,39 @@ fn get_config_path(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
     Ok(data_dir.join("config.json"))
 }
 
-fn load_config_sync(app_handle: &AppHandle) -> Result<AppConfig, AppError> {
-    let config_path = get_config_path(app_handle)?;
-    
+/// 不依赖 AppHandle 的应用目录，供命令行工具使用；与 BaseDirectory::Document 的解析结果一致
+pub(crate) fn default_app_dir() -> Result<PathBuf, AppError> {
+    dirs::document_dir()
+        .map(|dir| dir.join("PromptFlow"))
+        .ok_or_else(|| AppError::localized("IO_ERROR", "documents_dir_missing", &[]))
+}
+
+pub(crate) fn load_config_sync(app_handle: &AppHandle) -> Result<AppConfig, AppError> {
+    load_config_from(&get_config_path(app_handle)?)
+}
+
+pub(crate) fn load_config_from(config_path: &Path) -> Result<AppConfig, AppError> {
     if !config_path.exists() {
         return Ok(AppConfig::default());
     }
     
-    let content = fs::read_to_string(&config_path)?;
+    let content = fs::read_to_string(config_path)?;
     let config: AppConfig = serde_json::from_str(&content)
-        .map_err(|e| AppError::parse_error(format!("Failed to parse config: {}", e)))?;
+        .map_err(|e| AppError::localized("PARSE_ERROR", "parse_config_failed", &[&e]))?;
     
     Ok(config)
 }
 
-fn default_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+pub(crate) fn save_config_sync(app_handle: &AppHandle, config: &AppConfig) -> Result<(), AppError> {
+    write_atomic(&get_config_path(app_handle)?, serde_json::to_string_pretty(config)?)
+}
+
+pub(crate) fn default_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
     let document_dir = tauri::path::BaseDirectory::Document;
     let data_dir = app_handle
         .path()
         .resolve("PromptFlow", document_dir)
-        .map_err(|e| AppError::io_error(format!("Failed to resolve data directory: {}", e)))?;
+        .map_err(|e| AppError::localized("IO_ERROR", "resolve_dir_failed", &[&e]))?;
 
     Ok(data_dir)
 }
@@ -55,13 +83,16 @@ fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
-fn get_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
-    let config = load_config_sync(app_handle)?;
-    let storage_path = &config.storage.path;
+/// 按 storage.path 解析数据目录，未配置时使用 `default_dir`
+pub(crate) fn data_dir_from_config(config: &AppConfig, default_dir: PathBuf) -> Result<PathBuf, AppError> {
+    resolve_storage_path(&config.storage.path, default_dir)
+}
 
+/// 展开 `~` 并校验为绝对目录路径；空路径解析为 `default_dir`
+pub(crate) fn resolve_storage_path(storage_path: &str, default_dir: PathBuf) -> Result<PathBuf, AppError> {
     let trimmed = storage_path.trim();
     if trimmed.is_empty() {
-        return Ok(default_data_dir(app_handle)?);
+        return Ok(default_dir);
     }
 
     let mut path = if trimmed == "~" {
@@ -71,19 +102,15 @@ fn get_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
     };
 
     if path.components().next().is_none() {
-        return Ok(default_data_dir(app_handle)?);
+        return Ok(default_dir);
     }
 
     if !path.is_absolute() {
-        return Err(AppError::validation_error(
-            "storage.path must be an absolute path or empty (to use the default data directory)",
-        ));
+        return Err(AppError::localized("VALIDATION_ERROR", "storage_path_relative", &[]));
     }
 
     if path.exists() && !path.is_dir() {
-        return Err(AppError::validation_error(
-            "storage.path points to a file; it must be a directory",
-        ));
+        return Err(AppError::localized("VALIDATION_ERROR", "storage_path_is_file", &[]));
     }
 
     // Normalize away any trailing separators.
@@ -104,14 +131,24 @@ pub async fn init_repository(app_handle: AppHandle, path: String) -> Result<(),
     
     // 创建目录结构（如果不存在）
     if !target_path.exists() {
-        fs::create_dir_all(&target_path).map_err(|e| format!("无法创建目录: {}", e))?;
+        fs::create_dir_all(&target_path)
+            .map_err(|e| AppError::io_failed("create_dir_failed", target_path.display(), e).to_string())?;
     }
     
     // 创建子目录（如果不存在）- create_dir_all 对已存在的目录不会报错
-    fs::create_dir_all(&favorites_path).map_err(|e| format!("无法创建 favorites 目录: {}", e))?;
-    fs::create_dir_all(&templates_path).map_err(|e| format!("无法创建 templates 目录: {}", e))?;
-
-    install_starter_templates(&templates_path)?;
+    fs::create_dir_all(&favorites_path)
+        .map_err(|e| AppError::io_failed("create_dir_failed", favorites_path.display(), e).to_string())?;
+    fs::create_dir_all(&templates_path)
+        .map_err(|e| AppError::io_failed("create_dir_failed", templates_path.display(), e).to_string())?;
+
+    // 入门模板按当前配置的界面语言安装
+    let current_config = load_config_sync(&app_handle).ok();
+    let language = current_config
+        .as_ref()
+        .map(|c| Language::from_tag(&c.ui.language))
+        .unwrap_or_else(current_language);
+    let history = current_config.map(|c| c.history).unwrap_or_default();
+    install_default_packs(&target_path, language, &history).map_err(|e| e.to_string())?;
     
     // 只有当 config.json 不存在时才创建默认配置
     if !config_path.exists() {
@@ -119,9 +156,9 @@ pub async fn init_repository(app_handle: AppHandle, path: String) -> Result<(),
         config.storage.path = path.clone();
         
         let config_content = serde_json::to_string_pretty(&config)
-            .map_err(|e| format!("无法序列化配置: {}", e))?;
+            .map_err(|e| AppError::localized("PARSE_ERROR", "serialize_config_failed", &[&e]).to_string())?;
         fs::write(&config_path, config_content)
-            .map_err(|e| format!("无法写入配置文件: {}", e))?;
+            .map_err(|e| AppError::localized("IO_ERROR", "write_config_failed", &[&e]).to_string())?;
     }
     
     // Also update the app's main config to point to this new location
@@ -130,147 +167,172 @@ pub async fn init_repository(app_handle: AppHandle, path: String) -> Result<(),
     app_config.storage.path = path;
     
     let app_config_content = serde_json::to_string_pretty(&app_config)
-        .map_err(|e| format!("无法序列化应用配置: {}", e))?;
+        .map_err(|e| AppError::localized("PARSE_ERROR", "serialize_config_failed", &[&e]).to_string())?;
     fs::write(&app_config_path, app_config_content)
-        .map_err(|e| format!("无法写入应用配置: {}", e))?;
+        .map_err(|e| AppError::localized("IO_ERROR", "write_config_failed", &[&e]).to_string())?;
     
     Ok(())
 }
 
-fn install_starter_templates(templates_path: &PathBuf) -> Result<(), String> {
-    let now = generate_timestamp();
-
-    let starter_items: Vec<(&str, &str, Option<&str>, Vec<&str>, &str)> = vec![
-        (
-            "starter-engineering-workflow",
-            "Starter - 工程任务（Explore→Plan→Implement→Verify）",
-            Some("按工程化流程推进编码任务：先探索与计划，再实现与验证。"),
-            vec!["starter", "engineering", "workflow"],
-            "你是我的高级软件工程师与结对编程伙伴。\n\n# 目标\n完成我描述的工程任务，并确保可运行、可验证。\n\n# 输入上下文（请先读取/确认）\n- 仓库/项目：[[项目类型/语言/框架]]\n- 相关文件/目录：[[文件路径列表]]（如果我没给，你先问我或让我提供）\n- 约束：[[不能改动的部分/兼容性/截止时间/风格要求]]\n\n# 工作流程（必须按顺序）\n1) Explore：先复述你理解的目标，并列出你需要确认的 3-8 个关键问题（如缺省就提问）。\n2) Plan：给出一个简短计划（3-6 步），并明确风险点与验证方式。\n3) Implement：按计划实现（分步骤说明你改了什么）。\n4) Verify：给出自检清单（编译/测试/边界情况），并逐项说明你如何验证。\n\n# 输出格式（必须严格遵守）\n- **理解**：...\n- **计划**：...\n- **实现**：...\n- **验证**：...\n- **后续建议**：...\n",
-        ),
-        (
-            "starter-bug-debugging",
-            "Starter - Bug 定位与修复（复现→假设→验证→修复）",
-            Some("把模糊 bug 变成可复现、可验证的修复方案与回归清单。"),
-            vec!["starter", "debugging", "bugfix"],
-            "你是资深 Debug 工程师。你的目标是用最少的假设，快速定位根因并给出可验证修复方案。\n\n# Bug 描述\n[[现象/报错/截图文字]]\n\n# 环境信息\n- OS/浏览器/版本：[[...]]\n- 相关依赖版本：[[...]]\n- 日志/堆栈：[[粘贴日志；没有就说\"暂无\"；也可以提出需要哪些日志]]\n\n# 你必须产出\n1) 复现路径（最小复现步骤，按 1/2/3...）\n2) 根因假设列表（按概率排序，每条都给\"证据/线索/需要验证什么\"）\n3) 最优先的验证手段（我该先看哪些文件/加哪些日志/跑哪些命令）\n4) 修复方案（最小改动优先），并说明为什么能解决\n5) 回归测试清单（确保不引入新问题）\n\n# 输出格式（严格）\n- **复现步骤**：\n- **根因假设（按概率排序）**：\n- **验证计划**：\n- **修复方案**：\n- **回归测试**：\n",
-        ),
-        (
-            "starter-prd-breakdown",
-            "Starter - PRD 拆解（用户故事→任务→验收标准）",
-            Some("把需求转成工程可执行的 Epic/Story/Task 与可测试验收标准。"),
-            vec!["starter", "product", "planning"],
-            "你是产品 + 技术负责人，擅长把模糊需求变成可执行的开发计划。\n\n# 需求描述（原始）\n[[把你想到的需求直接粘贴，越口语越可以]]\n\n# 约束\n- 目标用户：[[...]]\n- 不做什么（Out of scope）：[[...]]\n- 时间/资源：[[...]]\n- 依赖系统：[[...]]\n\n# 你需要输出\n1) 需求澄清问题（最多 6 个，优先问\"影响方案选择\"的）\n2) 核心用户故事（1-3 条）\n3) 功能拆解（Epic → Stories → Tasks）\n4) 每个 Story 的验收标准（可测试、可判定，避免\"更好/更快\"）\n5) 风险与备选方案（技术/产品/数据）\n\n# 输出格式\n- **澄清问题**：\n- **用户故事**：\n- **拆解（Epic/Story/Task）**：\n- **验收标准**：\n- **风险与备选**：\n",
-        ),
-        (
-            "starter-gemini-official-template",
-            "Starter - Gemini 官方 Prompt 模板（Identity/Constraints/Format）",
-            Some("Google Gemini 官方推荐的结构化提示词模板骨架。"),
-            vec!["starter", "official", "gemini", "structure"],
-            "System Instruction:\n\n<role>\nYou are a specialized assistant for [[Domain/Role, e.g., Data Science / Senior Software Engineer]].\nYou are precise, analytical, and persistent.\n</role>\n\n<instructions>\n1. Plan: Analyze the task and create a step-by-step plan.\n2. Execute: Carry out the plan.\n3. Validate: Review your output against the user's task.\n4. Format: Present the final answer in the requested structure.\n</instructions>\n\n<constraints>\n- Verbosity: [[Low/Medium/High]]\n- Tone: [[Formal/Casual/Technical]]\n- Language: [[Chinese/English]]\n</constraints>\n\n<output_format>\nStructure your response as follows:\n1) Executive Summary: [[short overview]]\n2) Detailed Response: [[main content]]\n3) Validation Checklist: [[bullet checklist]]\n</output_format>\n\n\nUser Prompt:\n\n<context>\n[[Paste relevant docs / code snippets / background info here]]\n</context>\n\n<task>\n[[Insert specific request here]]\n</task>\n\n<final_instruction>\nThink step-by-step before answering, then provide the final response in the output_format.\n</final_instruction>\n",
-        ),
-        (
-            "starter-claude-code-workflow",
-            "Starter - Claude Code 官方工作流（Explore→Plan→Implement→Verify）",
-            Some("Anthropic Claude Code 官方工作流：先探索与计划，复杂任务用 checklist 推进。"),
-            vec!["starter", "official", "claude", "coding-workflow"],
-            "You are an expert engineer working in my codebase.\n\n## Workflow (follow in order)\n1) Explore:\n   - Identify the relevant files/modules.\n   - If you are unsure, ask me for the missing context or request specific files.\n   - Do NOT write code yet.\n\n2) Plan:\n   - Propose a short plan (3-6 steps).\n   - List risks / edge cases.\n   - Wait for my confirmation before coding.\n\n3) Implement:\n   - Make the minimal correct changes.\n   - Explain what changed at a high level.\n\n4) Verify:\n   - Provide a verification checklist (tests to run, cases to check).\n   - If you cannot run tests, explain what I should run and what success looks like.\n\n## Checklist / Scratchpad (for complex tasks)\nCreate a checklist of sub-tasks and tick them off one by one.\n\n## Output rules\n- Be specific and concrete.\n- Prefer bullet points and clear sections.\n",
-        ),
-        (
-            "starter-openai-gpt52-official-template",
-            "Starter - OpenAI GPT-5.2 官方模板（输出形状/范围/歧义/工具/结构化）",
-            Some("OpenAI Cookbook（GPT-5.2 Prompting Guide）提炼的官方提示词块，用于提升可靠性与可评估性。"),
-            vec!["starter", "official", "openai", "gpt-5.2", "structure"],
-            "# Role & Objective\nYou are [[role]]. Your objective is [[objective]].\n\n<output_verbosity_spec>\n- Default: 3–6 sentences or ≤5 bullets for typical answers.\n- For simple yes/no + short explanation questions: ≤2 sentences.\n- For complex multi-step or multi-file tasks:\n  - 1 short overview paragraph\n  - then ≤5 bullets tagged: What changed, Where, Risks, Next steps, Open questions.\n- Avoid long narrative paragraphs; prefer compact bullets and short sections.\n- Do not rephrase the user’s request unless it changes semantics.\n</output_verbosity_spec>\n\n<design_and_scope_constraints>\n- Implement EXACTLY and ONLY what the user requests.\n- No extra features, no added components, no UX embellishments.\n- Do NOT invent colors, shadows, tokens, animations, or new UI elements, unless requested or necessary.\n- If any instruction is ambiguous, choose the simplest valid interpretation.\n</design_and_scope_constraints>\n\n<long_context_handling>\n- For inputs longer than ~10k tokens:\n  - First, produce a short outline of key sections relevant to the request.\n  - Re-state constraints explicitly before answering.\n  - Anchor claims to sections; quote/paraphrase fine details (dates/thresholds/clauses).\n</long_context_handling>\n\n<uncertainty_and_ambiguity>\n- If ambiguous or underspecified:\n  - Ask up to 1–3 precise clarifying questions, OR\n  - Present 2–3 plausible interpretations with clearly labeled assumptions.\n- Never fabricate exact figures or references when uncertain.\n</uncertainty_and_ambiguity>\n\n<tool_usage_rules>\n- Prefer tools whenever you need fresh or user-specific data.\n- After any write/update tool call, restate: What changed, Where, Validation performed.\n</tool_usage_rules>\n\n<extraction_spec>\nUse this only when extracting structured data into JSON.\n- Follow the schema exactly (no extra fields): [[PASTE_JSON_SCHEMA]]\n- If a field is not present, set it to null rather than guessing.\n- Before returning, re-scan the source for missed fields.\n</extraction_spec>\n\n# User Task\n[[paste the task + context here]]\n",
-        ),
-    ];
-
-    for (id, title, description, tags, content) in starter_items {
-        let file_path = templates_path.join(format!("{}.md", id));
-        if file_path.exists() {
-            continue;
-        }
-
-        let prompt = PromptItem {
-            id: id.to_string(),
-            title: title.to_string(),
-            content: content.to_string(),
-            tags: tags.into_iter().map(|t| t.to_string()).collect(),
-            description: description.map(|d| d.to_string()),
-            use_count: 0,
-            last_used: None,
-            created_at: now.clone(),
-            updated_at: now.clone(),
-            file_path: file_path.to_string_lossy().to_string(),
-            folder: "templates".to_string(),
-        };
-
-        let markdown = serialize_markdown_prompt(&prompt);
-        fs::write(&file_path, markdown).map_err(|e| format!("Failed to write starter template: {}", e))?;
-    }
-
-    Ok(())
-}
-
 #[tauri::command]
 pub async fn export_data_dir(app_handle: AppHandle, target_dir: String) -> Result<String, String> {
     let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
-    let export_root = std::path::PathBuf::from(&target_dir);
+    let zip_path = export_archive(&data_dir, Path::new(&target_dir)).map_err(|e| e.to_string())?;
+    Ok(zip_path.to_string_lossy().to_string())
+}
 
+/// 把数据目录打包为带时间戳的 ZIP，返回生成的文件路径；保险库按磁盘上的密文原样打包
+pub(crate) fn export_archive(data_dir: &Path, export_root: &Path) -> Result<PathBuf, AppError> {
     if !export_root.exists() {
-        fs::create_dir_all(&export_root).map_err(|e| e.to_string())?;
+        fs::create_dir_all(export_root)?;
     }
 
     // Create ZIP file with timestamp
     let timestamp = generate_timestamp().replace(':', "-").replace(' ', "_");
     let zip_filename = format!("PromptFlow-Export-{}.zip", timestamp);
     let zip_path = export_root.join(&zip_filename);
+    let zip_error = |e: &dyn std::fmt::Display| AppError::io_failed("export_failed", zip_path.display(), e);
 
-    let file = File::create(&zip_path).map_err(|e| format!("Failed to create ZIP file: {}", e))?;
+    let file = File::create(&zip_path).map_err(|e| zip_error(&e))?;
     let mut zip = ZipWriter::new(file);
     let options = SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated)
         .unix_permissions(0o644);
 
     // Walk through data directory and add files to ZIP
-    for entry in WalkDir::new(&data_dir).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(data_dir).into_iter().filter_map(|e| e.ok()) {
         let path = entry.path();
-        let relative_path = path.strip_prefix(&data_dir).unwrap_or(path);
+        let relative_path = path.strip_prefix(data_dir).unwrap_or(path);
         let relative_str = relative_path.to_string_lossy();
 
         if path.is_file() {
             zip.start_file(relative_str.to_string(), options)
-                .map_err(|e| format!("Failed to add file to ZIP: {}", e))?;
+                .map_err(|e| zip_error(&e))?;
             
-            let mut f = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+            let mut f = File::open(path).map_err(|e| AppError::io_failed("read_file_failed", path.display(), e))?;
             let mut buffer = Vec::new();
-            f.read_to_end(&mut buffer).map_err(|e| format!("Failed to read file: {}", e))?;
-            zip.write_all(&buffer).map_err(|e| format!("Failed to write to ZIP: {}", e))?;
-        } else if path.is_dir() && path != data_dir.as_path() {
+            f.read_to_end(&mut buffer).map_err(|e| AppError::io_failed("read_file_failed", path.display(), e))?;
+            zip.write_all(&buffer).map_err(|e| zip_error(&e))?;
+        } else if path.is_dir() && path != data_dir {
             // Add directory entry
             let dir_name = format!("{}/", relative_str);
             zip.add_directory(dir_name, options)
-                .map_err(|e| format!("Failed to add directory to ZIP: {}", e))?;
+                .map_err(|e| zip_error(&e))?;
         }
     }
 
-    zip.finish().map_err(|e| format!("Failed to finalize ZIP: {}", e))?;
+    zip.finish().map_err(|e| zip_error(&e))?;
 
-    Ok(zip_path.to_string_lossy().to_string())
+    Ok(zip_path)
 }
 
-fn ensure_directories(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
-    let data_dir = get_data_dir(app_handle)?;
+/// 从导出的 ZIP 或单个 .md 文件导入 prompt；`folder` 为空时 ZIP 内的条目保留原目录
+pub(crate) fn import_prompts(
+    data_dir: &Path,
+    source: &Path,
+    folder: Option<&str>,
+    overwrite: bool,
+) -> Result<ImportReport, AppError> {
+    if let Some(folder) = folder {
+        if !PROMPT_FOLDERS.contains(&folder) {
+            return Err(AppError::localized("VALIDATION_ERROR", "unknown_folder", &[&folder]));
+        }
+    }
+
+    let mut report = ImportReport::default();
+    let is_zip = source.extension().map(|e| e.eq_ignore_ascii_case("zip")).unwrap_or(false);
+
+    if !is_zip {
+        let file_name = source
+            .file_name()
+            .and_then(|n| n.to_str())
+            .filter(|n| n.ends_with(".md"))
+            .ok_or_else(|| AppError::localized("VALIDATION_ERROR", "import_unsupported", &[]))?;
+        let content = fs::read_to_string(source)?;
+        import_prompt_file(data_dir, folder.unwrap_or("favorites"), file_name, &content, overwrite, &mut report)?;
+        return Ok(report);
+    }
+
+    let file = File::open(source)?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| AppError::localized("PARSE_ERROR", "zip_invalid", &[&e]))?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| AppError::localized("PARSE_ERROR", "zip_entry_invalid", &[&e]))?;
+        if !entry.is_file() {
+            continue;
+        }
+        // enclosed_name 会拒绝绝对路径和 `..`，防止写出数据目录
+        let Some(entry_path) = entry.enclosed_name() else {
+            continue;
+        };
 
+        let mut components = entry_path.iter().filter_map(|c| c.to_str());
+        let (Some(entry_folder), Some(file_name), None) = (components.next(), components.next(), components.next())
+        else {
+            continue;
+        };
+        if !PROMPT_FOLDERS.contains(&entry_folder) || !file_name.ends_with(".md") {
+            continue;
+        }
+
+        let mut content = String::new();
+        if entry.read_to_string(&mut content).is_err() {
+            report.skipped.push(entry_path.to_string_lossy().to_string());
+            continue;
+        }
+        let file_name = file_name.to_string();
+        import_prompt_file(data_dir, folder.unwrap_or(entry_folder), &file_name, &content, overwrite, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+fn import_prompt_file(
+    data_dir: &Path,
+    folder: &str,
+    file_name: &str,
+    content: &str,
+    overwrite: bool,
+    report: &mut ImportReport,
+) -> Result<(), AppError> {
+    let target = data_dir.join(folder).join(file_name);
+    let target_str = target.to_string_lossy().to_string();
+
+    let prompt = match parse_markdown_prompt(content, &target_str, folder) {
+        Ok(prompt) if overwrite || !target.exists() => prompt,
+        _ => {
+            report.skipped.push(format!("{}/{}", folder, file_name));
+            return Ok(());
+        }
+    };
+
+    write_atomic(&target, content)?;
+    report.imported.push(prompt);
+    Ok(())
+}
+
+pub(crate) fn ensure_directories(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    let config = load_config_sync(app_handle)?;
+    prepare_data_dir(data_dir_from_config(&config, default_data_dir(app_handle)?)?, &config.history)
+}
+
+/// 校验数据目录并创建 prompt 子目录；首次初始化时安装默认模板包，修订按 `history` 保留
+/// 只校验数据目录，不创建目录也不安装模板包，供只读访问使用
+pub(crate) fn validate_data_dir(data_dir: &Path) -> Result<(), AppError> {
     if data_dir.components().next().is_none() {
-        return Err(AppError::validation_error("Resolved data directory is empty"));
+        return Err(AppError::localized("VALIDATION_ERROR", "data_dir_empty", &[]));
     }
 
     if data_dir.exists() && !data_dir.is_dir() {
-        return Err(AppError::validation_error(
-            "Resolved data directory points to a file; it must be a directory",
-        ));
+        return Err(AppError::localized("VALIDATION_ERROR", "data_dir_is_file", &[]));
     }
+    Ok(())
+}
+
+pub(crate) fn prepare_data_dir(data_dir: PathBuf, history: &HistoryConfig) -> Result<PathBuf, AppError> {
+    validate_data_dir(&data_dir)?;
 
     let favorites_dir = data_dir.join("favorites");
     let templates_dir = data_dir.join("templates");
@@ -285,8 +347,10 @@ fn ensure_directories(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
         fs::create_dir_all(&templates_dir)?;
     }
     
-    // Install starter templates if they don't exist
-    let _ = install_starter_templates(&templates_dir);
+    // 仅在首次初始化时安装默认模板包
+    if let Err(e) = install_default_packs(&data_dir, current_language(), history) {
+        eprintln!("[PromptFlow] Failed to install default template packs: {}", e);
+    }
     
     Ok(data_dir)
 }
@@ -299,17 +363,18 @@ pub async fn get_data_directory(app_handle: AppHandle) -> Result<String, String>
 
 #[tauri::command]
 pub async fn read_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))
+    fs::read_to_string(&path).map_err(|e| AppError::io_failed("read_file_failed", &path, e).to_string())
 }
 
 #[tauri::command]
 pub async fn write_file(path: String, content: String) -> Result<(), String> {
     if let Some(parent) = std::path::Path::new(&path).parent() {
         if !parent.exists() {
-            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io_failed("create_dir_failed", parent.display(), e).to_string())?;
         }
     }
-    fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))
+    fs::write(&path, content).map_err(|e| AppError::io_failed("write_file_failed", &path, e).to_string())
 }
 
 #[tauri::command]
@@ -320,7 +385,7 @@ pub async fn list_files(dir: String, extension: Option<String>) -> Result<Vec<Fi
     }
 
     let mut files = vec![];
-    let entries = fs::read_dir(path).map_err(|e| format!("Failed to read directory: {}", e))?;
+    let entries = fs::read_dir(path).map_err(|e| AppError::io_failed("read_dir_failed", &dir, e).to_string())?;
 
     for entry in entries.flatten() {
         let file_path = entry.path();
@@ -361,55 +426,58 @@ pub async fn list_files(dir: String, extension: Option<String>) -> Result<Vec<Fi
 }
 
 #[tauri::command]
-pub async fn delete_file(path: String) -> Result<(), String> {
-    fs::remove_file(&path).map_err(|e| format!("Failed to delete file: {}", e))
-}
+pub async fn delete_file(app_handle: AppHandle, path: String) -> Result<(), String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    // 比较前先规范化，避免 `..` 或符号链接绕过；数据目录之外的文件一律拒绝删除
+    let (file_path, canonical_dir) = match (PathBuf::from(&path).canonicalize(), data_dir.canonicalize()) {
+        (Ok(file), Ok(dir)) => (file, dir),
+        _ => return Err(AppError::localized("NOT_FOUND", "file_not_found", &[&path]).to_string()),
+    };
+    if !file_path.starts_with(&canonical_dir) {
+        return Err(AppError::localized("PERMISSION_DENIED", "path_outside_data_dir", &[&path]).to_string());
+    }
 
-#[tauri::command]
-pub async fn file_exists(path: String) -> Result<bool, String> {
-    Ok(std::path::Path::new(&path).exists())
-}
+    // 数据目录中的 prompt 文件按 prompt 处理，便于恢复到原目录
+    let folder = file_path
+        .parent()
+        .filter(|parent| parent.parent() == Some(canonical_dir.as_path()))
+        .and_then(|parent| parent.file_name())
+        .and_then(|name| name.to_str())
+        .filter(|name| PROMPT_FOLDERS.contains(name));
+    let is_prompt = folder.is_some() && file_path.extension().map(|e| e == "md").unwrap_or(false);
 
-#[tauri::command]
-pub async fn get_all_prompts(app_handle: AppHandle) -> Result<Vec<PromptItem>, String> {
-    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
-    let mut prompts = vec![];
+    let stem = file_path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let title = file_path.file_name().and_then(|s| s.to_str()).unwrap_or_default();
 
-    for folder in ["favorites", "templates"] {
-        let folder_path = data_dir.join(folder);
-        if folder_path.exists() {
-            let entries = fs::read_dir(&folder_path).map_err(|e| e.to_string())?;
-            for entry in entries.flatten() {
-                let file_path = entry.path();
-                if file_path.extension().map(|e| e == "md").unwrap_or(false) {
-                    if let Ok(content) = fs::read_to_string(&file_path) {
-                        let path_str = file_path.to_string_lossy().to_string();
-                        if let Ok(prompt) = parse_markdown_prompt(&content, &path_str, folder) {
-                            prompts.push(prompt);
-                        }
-                    }
-                }
-            }
-        }
+    if is_prompt {
+        move_to_trash(&data_dir, &file_path, folder, Some(stem), title)
+    } else {
+        move_to_trash(&data_dir, &file_path, None, None, title)
     }
+    .map_err(|e| e.to_string())?;
 
-    Ok(prompts)
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn get_favorites(app_handle: AppHandle) -> Result<Vec<PromptItem>, String> {
-    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
-    let folder_path = data_dir.join("favorites");
+pub async fn file_exists(path: String) -> Result<bool, String> {
+    Ok(std::path::Path::new(&path).exists())
+}
+
+pub(crate) const PROMPT_FOLDERS: [&str; 2] = ["favorites", "templates"];
+
+pub(crate) fn load_folder_prompts(data_dir: &Path, folder: &str) -> Result<Vec<PromptItem>, AppError> {
+    let folder_path = data_dir.join(folder);
     let mut prompts = vec![];
 
     if folder_path.exists() {
-        let entries = fs::read_dir(&folder_path).map_err(|e| e.to_string())?;
+        let entries = fs::read_dir(&folder_path)?;
         for entry in entries.flatten() {
             let file_path = entry.path();
             if file_path.extension().map(|e| e == "md").unwrap_or(false) {
-                if let Ok(content) = fs::read_to_string(&file_path) {
+                if let Ok(content) = read_prompt_file(&file_path) {
                     let path_str = file_path.to_string_lossy().to_string();
-                    if let Ok(prompt) = parse_markdown_prompt(&content, &path_str, "favorites") {
+                    if let Ok(prompt) = parse_markdown_prompt(&content, &path_str, folder) {
                         prompts.push(prompt);
                     }
                 }
@@ -417,42 +485,131 @@ pub async fn get_favorites(app_handle: AppHandle) -> Result<Vec<PromptItem>, Str
         }
     }
 
+    prompts.sort_by(manual_order);
+    Ok(prompts)
+}
+
+/// 目录内的展示顺序：置顶优先，其次按 sort_order，未排序的按标题排在最后
+pub(crate) fn manual_order(a: &PromptItem, b: &PromptItem) -> Ordering {
+    b.pinned
+        .cmp(&a.pinned)
+        .then_with(|| match (a.sort_order, b.sort_order) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        })
+        .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// 拼接 `<folder>/<id>.md` 之前校验目录与 id，避免越出数据目录
+pub(crate) fn check_prompt_ref(folder: &str, id: &str) -> Result<(), AppError> {
+    if !PROMPT_FOLDERS.contains(&folder) && folder != VAULT_FOLDER {
+        return Err(AppError::localized("VALIDATION_ERROR", "unknown_folder", &[&folder]));
+    }
+    if !is_safe_file_name(id) {
+        return Err(AppError::localized("VALIDATION_ERROR", "invalid_prompt_id", &[&id]));
+    }
+    Ok(())
+}
+
+/// 读取单个 prompt，文件不存在时返回 NOT_FOUND
+pub(crate) fn read_prompt(data_dir: &Path, folder: &str, id: &str) -> Result<PromptItem, AppError> {
+    let file_path = data_dir.join(folder).join(format!("{}.md", id));
+    if !file_path.is_file() {
+        return Err(AppError::localized("NOT_FOUND", "prompt_not_found", &[&format!("{}/{}", folder, id)]));
+    }
+
+    let content = read_prompt_file(&file_path)?;
+    parse_markdown_prompt(&content, &file_path.to_string_lossy(), folder)
+}
+
+/// 加载所有目录的 prompt；`include_vault` 为 true 且保险库已解锁时也包含保险库，
+/// 只有面向界面的列表、搜索、快捷键等才应传 true
+pub(crate) fn load_all_prompts(data_dir: &Path, include_vault: bool) -> Result<Vec<PromptItem>, AppError> {
+    let mut prompts = vec![];
+    for folder in PROMPT_FOLDERS {
+        prompts.extend(load_folder_prompts(data_dir, folder)?);
+    }
+    if include_vault && is_vault_unlocked(data_dir) {
+        prompts.extend(load_folder_prompts(data_dir, VAULT_FOLDER)?);
+    }
     Ok(prompts)
 }
 
 #[tauri::command]
-pub async fn get_templates(app_handle: AppHandle) -> Result<Vec<PromptItem>, String> {
+pub async fn get_all_prompts(app_handle: AppHandle) -> Result<Vec<PromptItem>, String> {
     let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
-    let folder_path = data_dir.join("templates");
-    let mut prompts = vec![];
+    let mut prompts = load_all_prompts(&data_dir, true).map_err(|e| e.to_string())?;
+    apply_usage_stats(&data_dir, &mut prompts);
+    tag_active_repository(&app_handle, &data_dir, &mut prompts);
+    prompts.extend(load_shared_layers(&app_handle, &data_dir, None));
+    Ok(prompts)
+}
 
-    if folder_path.exists() {
-        let entries = fs::read_dir(&folder_path).map_err(|e| e.to_string())?;
-        for entry in entries.flatten() {
-            let file_path = entry.path();
-            if file_path.extension().map(|e| e == "md").unwrap_or(false) {
-                if let Ok(content) = fs::read_to_string(&file_path) {
-                    let path_str = file_path.to_string_lossy().to_string();
-                    if let Ok(prompt) = parse_markdown_prompt(&content, &path_str, "templates") {
-                        prompts.push(prompt);
-                    }
-                }
-            }
-        }
-    }
+#[tauri::command]
+pub async fn get_favorites(app_handle: AppHandle) -> Result<Vec<PromptItem>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let mut prompts = load_folder_prompts(&data_dir, "favorites").map_err(|e| e.to_string())?;
+    apply_usage_stats(&data_dir, &mut prompts);
+    tag_active_repository(&app_handle, &data_dir, &mut prompts);
+    prompts.extend(load_shared_layers(&app_handle, &data_dir, Some("favorites")));
+    Ok(prompts)
+}
 
+#[tauri::command]
+pub async fn get_templates(app_handle: AppHandle) -> Result<Vec<PromptItem>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let mut prompts = load_folder_prompts(&data_dir, "templates").map_err(|e| e.to_string())?;
+    apply_usage_stats(&data_dir, &mut prompts);
+    tag_active_repository(&app_handle, &data_dir, &mut prompts);
+    prompts.extend(load_shared_layers(&app_handle, &data_dir, Some("templates")));
     Ok(prompts)
 }
 
 #[tauri::command]
 pub async fn create_prompt(app_handle: AppHandle, input: CreatePromptInput) -> Result<PromptItem, String> {
     let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+
+    let mut input = input;
+    input.hotkey = input.hotkey.map(|h| h.trim().to_string()).filter(|h| !h.is_empty());
+    if let Some(ref hotkey) = input.hotkey {
+        check_prompt_hotkey(&app_handle, &data_dir, hotkey, &input.folder, &sanitize_filename(&input.title))
+            .map_err(|e| e.to_string())?;
+    }
+
+    let prompt = write_new_prompt(&data_dir, input).map_err(|e| e.to_string())?;
+
+    record_prompt_revision(&app_handle, &data_dir, &prompt);
+    auto_commit(&app_handle, &data_dir, &format!("Add prompt: {}", commit_title(&prompt)));
+    if prompt.hotkey.is_some() {
+        refresh_prompt_hotkeys(&app_handle);
+    }
+    refresh_tray_menu(&app_handle);
     
-    let folder = if input.folder == "templates" { "templates" } else { "favorites" };
+    Ok(prompt)
+}
+
+/// 写入新 prompt 文件；快捷键校验、修订记录与自动提交由调用方负责
+pub(crate) fn write_new_prompt(data_dir: &Path, input: CreatePromptInput) -> Result<PromptItem, AppError> {
+    let folder = match input.folder.as_str() {
+        "templates" => "templates",
+        VAULT_FOLDER => VAULT_FOLDER,
+        _ => "favorites",
+    };
     let folder_path = data_dir.join(folder);
     
-    let file_name = sanitize_filename(&input.title);
+    // 保险库中的文件名不能透露标题
+    let file_name = if folder == VAULT_FOLDER {
+        generate_id()
+    } else {
+        sanitize_filename(&input.title)
+    };
     let file_path = folder_path.join(format!("{}.md", file_name));
+    if file_path.exists() {
+        return Err(AppError::localized("VALIDATION_ERROR", "prompt_exists", &[&format!("{}/{}", folder, file_name)]));
+    }
     
     let now = generate_timestamp();
     let prompt = PromptItem {
@@ -467,10 +624,16 @@ pub async fn create_prompt(app_handle: AppHandle, input: CreatePromptInput) -> R
         updated_at: now,
         file_path: file_path.to_string_lossy().to_string(),
         folder: folder.to_string(),
+        hotkey: input.hotkey,
+        pinned: false,
+        sort_order: None,
+        repository: None,
+        read_only: false,
+        forked_from: None,
     };
     
     let markdown = serialize_markdown_prompt(&prompt);
-    fs::write(&file_path, markdown).map_err(|e| format!("Failed to write file: {}", e))?;
+    write_prompt_file(&file_path, &markdown)?;
     
     Ok(prompt)
 }
@@ -481,16 +644,18 @@ pub async fn update_prompt(
     id: String,
     folder: String,
     updates: UpdatePromptInput,
+    repository: Option<String>,
 ) -> Result<PromptItem, String> {
     let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    ensure_writable(&app_handle, &data_dir, repository.as_deref(), &folder, &id).map_err(|e| e.to_string())?;
     let folder_path = data_dir.join(&folder);
     let file_path = folder_path.join(format!("{}.md", id));
     
     if !file_path.exists() {
-        return Err(format!("Prompt not found: {}", id));
+        return Err(AppError::localized("NOT_FOUND", "prompt_not_found", &[&id]).to_string());
     }
     
-    let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+    let content = read_prompt_file(&file_path).map_err(|e| e.to_string())?;
     let path_str = file_path.to_string_lossy().to_string();
     let mut prompt = parse_markdown_prompt(&content, &path_str, &folder).map_err(|e| e.to_string())?;
     
@@ -506,23 +671,58 @@ pub async fn update_prompt(
     if let Some(description) = updates.description {
         prompt.description = Some(description);
     }
+
+    let previous_hotkey = prompt.hotkey.clone();
+    if let Some(hotkey) = updates.hotkey {
+        let hotkey = hotkey.trim();
+        if hotkey.is_empty() {
+            prompt.hotkey = None;
+        } else {
+            check_prompt_hotkey(&app_handle, &data_dir, hotkey, &folder, &id).map_err(|e| e.to_string())?;
+            prompt.hotkey = Some(hotkey.to_string());
+        }
+    }
     
     prompt.updated_at = generate_timestamp();
     
     let markdown = serialize_markdown_prompt(&prompt);
-    fs::write(&file_path, markdown).map_err(|e| format!("Failed to write file: {}", e))?;
+    write_prompt_file(&file_path, &markdown).map_err(|e| e.to_string())?;
+
+    record_prompt_revision(&app_handle, &data_dir, &prompt);
+    auto_commit(&app_handle, &data_dir, &format!("Update prompt: {}", commit_title(&prompt)));
+    if prompt.hotkey != previous_hotkey {
+        refresh_prompt_hotkeys(&app_handle);
+    }
+    refresh_tray_menu(&app_handle);
     
     Ok(prompt)
 }
 
 #[tauri::command]
-pub async fn delete_prompt(app_handle: AppHandle, id: String, folder: String) -> Result<(), String> {
+pub async fn delete_prompt(
+    app_handle: AppHandle,
+    id: String,
+    folder: String,
+    repository: Option<String>,
+) -> Result<(), String> {
     let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    ensure_writable(&app_handle, &data_dir, repository.as_deref(), &folder, &id).map_err(|e| e.to_string())?;
     let folder_path = data_dir.join(&folder);
     let file_path = folder_path.join(format!("{}.md", id));
     
     if file_path.exists() {
-        fs::remove_file(&file_path).map_err(|e| format!("Failed to delete file: {}", e))?;
+        let path_str = file_path.to_string_lossy().to_string();
+        // 回收站元数据是明文，保险库中的 prompt 只记录 id
+        let title = read_prompt_file(&file_path)
+            .ok()
+            .and_then(|content| parse_markdown_prompt(&content, &path_str, &folder).ok())
+            .map(|prompt| commit_title(&prompt).to_string())
+            .unwrap_or_else(|| id.clone());
+
+        move_to_trash(&data_dir, &file_path, Some(&folder), Some(&id), &title).map_err(|e| e.to_string())?;
+        auto_commit(&app_handle, &data_dir, &format!("Delete prompt: {}/{}", folder, id));
+        refresh_prompt_hotkeys(&app_handle);
+        refresh_tray_menu(&app_handle);
     }
     
     Ok(())
@@ -531,24 +731,17 @@ pub async fn delete_prompt(app_handle: AppHandle, id: String, folder: String) ->
 #[tauri::command]
 pub async fn increment_use_count(app_handle: AppHandle, id: String, folder: String) -> Result<PromptItem, String> {
     let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
-    let folder_path = data_dir.join(&folder);
-    let file_path = folder_path.join(format!("{}.md", id));
-    
-    if !file_path.exists() {
-        return Err(format!("Prompt not found: {}", id));
+    let (mut prompt, shared) = read_layered_prompt(&app_handle, &data_dir, &folder, &id).map_err(|e| e.to_string())?;
+    // 共享仓库的 prompt 不记录使用，避免写入只读目录
+    if shared {
+        return Ok(prompt);
     }
-    
-    let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
-    let path_str = file_path.to_string_lossy().to_string();
-    let mut prompt = parse_markdown_prompt(&content, &path_str, &folder).map_err(|e| e.to_string())?;
-    
-    prompt.use_count += 1;
-    prompt.last_used = Some(generate_timestamp());
-    prompt.updated_at = generate_timestamp();
-    
-    let markdown = serialize_markdown_prompt(&prompt);
-    fs::write(&file_path, markdown).map_err(|e| format!("Failed to write file: {}", e))?;
-    
+
+    // 使用记录写入事件日志，不再重写 prompt 文件
+    record_usage_event(&data_dir, &id, &folder, UsageAction::Copy, None).map_err(|e| e.to_string())?;
+    apply_usage_stats(&data_dir, std::slice::from_mut(&mut prompt));
+
+    refresh_tray_menu(&app_handle);
     Ok(prompt)
 }
 
@@ -558,7 +751,8 @@ pub async fn init_templates(app_handle: AppHandle) -> Result<(), String> {
     let templates_path = data_dir.join("templates");
 
     if !templates_path.exists() {
-        fs::create_dir_all(&templates_path).map_err(|e| format!("Failed to create templates directory: {}", e))?;
+        fs::create_dir_all(&templates_path)
+            .map_err(|e| AppError::io_failed("create_dir_failed", templates_path.display(), e).to_string())?;
     }
 
     let template_files = [
@@ -585,17 +779,59 @@ pub async fn init_templates(app_handle: AppHandle) -> Result<(), String> {
                 updated_at: generate_timestamp(),
                 file_path: file_path.to_string_lossy().to_string(),
                 folder: "templates".to_string(),
+                hotkey: None,
+                pinned: false,
+                sort_order: None,
+                repository: None,
+                read_only: false,
+                forked_from: None,
             };
 
             let markdown = serialize_markdown_prompt(&prompt);
-            fs::write(&file_path, markdown).map_err(|e| format!("Failed to write template file: {}", e))?;
+            fs::write(&file_path, markdown)
+                .map_err(|e| AppError::io_failed("write_file_failed", file_path.display(), e).to_string())?;
         }
     }
 
     Ok(())
 }
 
-fn sanitize_filename(name: &str) -> String {
+// 修订记录失败不应阻止保存本身
+pub(crate) fn record_prompt_revision(app_handle: &AppHandle, data_dir: &Path, prompt: &PromptItem) {
+    let history_config = load_config_sync(app_handle).map(|c| c.history).unwrap_or_default();
+    if let Err(e) = record_revision(data_dir, prompt, &history_config) {
+        eprintln!("[PromptFlow] Failed to record revision for {}: {}", prompt.id, e);
+    }
+}
+
+fn check_prompt_hotkey(
+    app_handle: &AppHandle,
+    data_dir: &Path,
+    hotkey: &str,
+    folder: &str,
+    prompt_id: &str,
+) -> Result<(), AppError> {
+    let prompts = load_all_prompts(data_dir, true)?;
+    validate_prompt_hotkey(app_handle, hotkey, folder, prompt_id, &prompts).map(|_| ())
+}
+
+/// 重新注册所有 prompt 快捷键；无法注册的会通过 prompt-hotkey-conflicts 事件通知前端
+pub(crate) fn refresh_prompt_hotkeys(app_handle: &AppHandle) {
+    let prompts = match ensure_directories(app_handle).and_then(|data_dir| load_all_prompts(&data_dir, true)) {
+        Ok(prompts) => prompts,
+        Err(e) => {
+            eprintln!("[PromptFlow] Failed to load prompt hotkeys: {}", e);
+            return;
+        }
+    };
+
+    let conflicts = sync_prompt_hotkeys(app_handle, &prompts);
+    if !conflicts.is_empty() {
+        let _ = app_handle.emit("prompt-hotkey-conflicts", &conflicts);
+    }
+}
+
+pub(crate) fn sanitize_filename(name: &str) -> String {
     name.chars()
         .map(|c| {
             if c.is_alphanumeric() || c == '-' || c == '_' {
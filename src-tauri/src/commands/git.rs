@@ -0,0 +1,110 @@
+use super::file::{ensure_directories, load_config_sync, refresh_prompt_hotkeys};
+use crate::models::{GitConfig, GitStatus, GitSyncResult};
+use crate::services::git::GitRepo;
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::AppError;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+fn open_repo(app_handle: &AppHandle) -> Result<(GitRepo, GitConfig), AppError> {
+    let data_dir = ensure_directories(app_handle)?;
+    let config = load_config_sync(app_handle)?.git;
+    Ok((GitRepo::new(data_dir), config))
+}
+
+fn open_remote_repo(app_handle: &AppHandle) -> Result<(GitRepo, GitConfig), AppError> {
+    let (repo, config) = open_repo(app_handle)?;
+
+    if !repo.is_initialized() {
+        return Err(AppError::localized("GIT_ERROR", "git_not_initialized", &[]));
+    }
+    if config.remote_url.trim().is_empty() {
+        return Err(AppError::localized("VALIDATION_ERROR", "remote_not_configured", &[]));
+    }
+
+    repo.set_remote(config.remote_url.trim())?;
+    Ok((repo, config))
+}
+
+/// 在 prompt 变更后自动提交；失败只记录日志，不影响文件操作本身
+pub(crate) fn auto_commit(app_handle: &AppHandle, data_dir: &Path, message: &str) {
+    if let Ok(config) = load_config_sync(app_handle) {
+        auto_commit_with(&config.git, data_dir, message);
+    }
+}
+
+pub(crate) fn auto_commit_with(config: &GitConfig, data_dir: &Path, message: &str) {
+    if !config.enabled || !config.auto_commit {
+        return;
+    }
+
+    let repo = GitRepo::new(data_dir);
+    if !repo.is_initialized() {
+        return;
+    }
+
+    if let Err(e) = repo.commit_all(message) {
+        eprintln!("[PromptFlow] Git auto-commit failed: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn git_init(app_handle: AppHandle) -> Result<GitStatus, String> {
+    let (repo, config) = open_repo(&app_handle).map_err(|e| e.to_string())?;
+
+    repo.init(&config.branch).map_err(|e| e.to_string())?;
+    if !config.remote_url.trim().is_empty() {
+        repo.set_remote(config.remote_url.trim()).map_err(|e| e.to_string())?;
+    }
+    repo.commit_all("Initialize prompt repository").map_err(|e| e.to_string())?;
+
+    repo.status().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_status(app_handle: AppHandle) -> Result<GitStatus, String> {
+    let (repo, _) = open_repo(&app_handle).map_err(|e| e.to_string())?;
+    repo.status().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_commit(app_handle: AppHandle, message: Option<String>) -> Result<bool, String> {
+    let (repo, config) = open_repo(&app_handle).map_err(|e| e.to_string())?;
+    if !config.enabled {
+        return Ok(false);
+    }
+    let message = message
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| "Update prompts".to_string());
+    repo.commit_all(&message).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_pull(app_handle: AppHandle) -> Result<GitSyncResult, String> {
+    let (repo, config) = open_remote_repo(&app_handle).map_err(|e| e.to_string())?;
+
+    // 拉取前先提交本地改动，否则 git 会拒绝合并
+    repo.commit_all("Save local changes before pull").map_err(|e| e.to_string())?;
+    let result = repo.pull(&config.branch).map_err(|e| e.to_string())?;
+    refresh_prompt_hotkeys(&app_handle);
+    refresh_tray_menu(&app_handle);
+
+    if !result.conflicts.is_empty() {
+        let _ = app_handle.emit("git-conflicts", &result.conflicts);
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn git_push(app_handle: AppHandle) -> Result<GitSyncResult, String> {
+    let (repo, config) = open_remote_repo(&app_handle).map_err(|e| e.to_string())?;
+    repo.push(&config.branch).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_abort_merge(app_handle: AppHandle) -> Result<GitStatus, String> {
+    let (repo, _) = open_repo(&app_handle).map_err(|e| e.to_string())?;
+    repo.abort_merge().map_err(|e| e.to_string())?;
+    repo.status().map_err(|e| e.to_string())
+}
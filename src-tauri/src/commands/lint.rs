@@ -0,0 +1,83 @@
+use super::file::{ensure_directories, refresh_prompt_hotkeys, PROMPT_FOLDERS};
+use super::git::auto_commit;
+use crate::models::{LintIssue, LintReport, LintSeverity};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{lint_prompt_file, write_atomic, AppError};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// 检查所有 prompt 文件；`fix` 为真时应用安全修复并重新检查
+fn lint_data_dir(data_dir: &Path, fix: bool) -> Result<LintReport, AppError> {
+    let mut report = LintReport::default();
+    let mut ids: HashMap<String, Vec<String>> = HashMap::new();
+
+    for folder in PROMPT_FOLDERS {
+        let folder_path = data_dir.join(folder);
+        if !folder_path.is_dir() {
+            continue;
+        }
+
+        for entry in fs::read_dir(&folder_path)?.flatten() {
+            let path = entry.path();
+            if !path.is_file() || path.extension().map(|e| e != "md").unwrap_or(true) {
+                continue;
+            }
+            report.files_scanned += 1;
+
+            let path_str = path.to_string_lossy().to_string();
+            if let Some(id) = path.file_stem().and_then(|s| s.to_str()) {
+                ids.entry(id.to_lowercase()).or_default().push(path_str.clone());
+            }
+
+            let bytes = fs::read(&path)?;
+            let mut result = lint_prompt_file(&bytes, &path_str, folder);
+
+            if fix {
+                if let Some(content) = result.fixed_content.take() {
+                    write_atomic(&path, &content)?;
+                    report
+                        .fixed
+                        .extend(result.issues.into_iter().filter(|issue| issue.fixable));
+                    result = lint_prompt_file(content.as_bytes(), &path_str, folder);
+                }
+            }
+            report.issues.extend(result.issues);
+        }
+    }
+
+    for paths in ids.values().filter(|paths| paths.len() > 1) {
+        for path in paths {
+            let others: Vec<&str> = paths.iter().filter(|p| *p != path).map(String::as_str).collect();
+            report.issues.push(LintIssue {
+                file_path: path.clone(),
+                line: None,
+                code: "duplicate_id".to_string(),
+                severity: LintSeverity::Warning,
+                message: format!("Prompt ID is also used by {}", others.join(", ")),
+                suggestion: "Rename one of the files so links and hotkeys resolve to a single prompt".to_string(),
+                fixable: false,
+            });
+        }
+    }
+
+    report
+        .issues
+        .sort_by(|a, b| a.file_path.cmp(&b.file_path).then_with(|| a.line.cmp(&b.line)));
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn lint_library(app_handle: AppHandle, fix: Option<bool>) -> Result<LintReport, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let report = lint_data_dir(&data_dir, fix.unwrap_or(false)).map_err(|e| e.to_string())?;
+
+    if !report.fixed.is_empty() {
+        auto_commit(&app_handle, &data_dir, "Fix prompt lint issues");
+        refresh_prompt_hotkeys(&app_handle);
+        refresh_tray_menu(&app_handle);
+    }
+
+    Ok(report)
+}
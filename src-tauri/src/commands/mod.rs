@@ -1,7 +1,37 @@
 pub mod file;
 pub mod config;
 pub mod window;
+pub mod polish;
+pub mod revision;
+pub mod git;
+pub mod trash;
+pub mod tags;
+pub mod clipboard;
+pub mod server;
+pub mod duplicates;
+pub mod lint;
+pub mod usage;
+pub mod query;
+pub mod order;
+pub mod packs;
+pub mod repository;
+pub mod vault;
 
 pub use file::*;
 pub use config::*;
 pub use window::*;
+pub use polish::*;
+pub use revision::*;
+pub use git::*;
+pub use trash::*;
+pub use tags::*;
+pub use clipboard::*;
+pub use server::*;
+pub use duplicates::*;
+pub use lint::*;
+pub use usage::*;
+pub use query::*;
+pub use order::*;
+pub use packs::*;
+pub use repository::*;
+pub use vault::*;
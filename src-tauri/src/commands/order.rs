@@ -0,0 +1,90 @@
+use super::file::{ensure_directories, load_folder_prompts, read_prompt, PROMPT_FOLDERS};
+use super::git::auto_commit;
+use super::repository::ensure_writable;
+use super::usage::apply_usage_stats;
+use super::vault::{commit_title, write_prompt_file, VAULT_FOLDER};
+use crate::models::PromptItem;
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{serialize_markdown_prompt, AppError};
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::AppHandle;
+
+// 置顶与排序只是展示元数据，不更新 updated_at，也不记录修订
+fn write_prompt(prompt: &PromptItem) -> Result<(), AppError> {
+    write_prompt_file(Path::new(&prompt.file_path), &serialize_markdown_prompt(prompt))
+}
+
+fn check_folder(folder: &str) -> Result<(), AppError> {
+    if PROMPT_FOLDERS.contains(&folder) || folder == VAULT_FOLDER {
+        Ok(())
+    } else {
+        Err(AppError::localized("VALIDATION_ERROR", "unknown_folder", &[&folder]))
+    }
+}
+
+/// `ids` 中的 prompt 依次获得 0..n 的位置，目录中其余 prompt 清除手动排序
+fn apply_order(data_dir: &Path, folder: &str, ids: &[String]) -> Result<Vec<PromptItem>, AppError> {
+    check_folder(folder)?;
+    let mut seen = HashSet::new();
+    if let Some(dup) = ids.iter().find(|id| !seen.insert(id.as_str())) {
+        return Err(AppError::localized("VALIDATION_ERROR", "duplicate_order_id", &[dup]));
+    }
+
+    let mut prompts = load_folder_prompts(data_dir, folder)?;
+    if let Some(missing) = ids.iter().find(|id| !prompts.iter().any(|p| &p.id == *id)) {
+        return Err(AppError::localized("NOT_FOUND", "prompt_not_found", &[&format!("{}/{}", folder, missing)]));
+    }
+
+    for prompt in prompts.iter_mut() {
+        let order = ids.iter().position(|id| *id == prompt.id).map(|i| i as u32);
+        if prompt.sort_order != order {
+            prompt.sort_order = order;
+            write_prompt(prompt)?;
+        }
+    }
+
+    load_folder_prompts(data_dir, folder)
+}
+
+#[tauri::command]
+pub async fn set_prompt_pinned(
+    app_handle: AppHandle,
+    id: String,
+    folder: String,
+    pinned: bool,
+) -> Result<PromptItem, String> {
+    check_folder(&folder).map_err(|e| e.to_string())?;
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    ensure_writable(&app_handle, &data_dir, None, &folder, &id).map_err(|e| e.to_string())?;
+    let mut prompt = read_prompt(&data_dir, &folder, &id).map_err(|e| e.to_string())?;
+
+    if prompt.pinned != pinned {
+        prompt.pinned = pinned;
+        write_prompt(&prompt).map_err(|e| e.to_string())?;
+
+        let action = if pinned { "Pin" } else { "Unpin" };
+        auto_commit(&app_handle, &data_dir, &format!("{} prompt: {}", action, commit_title(&prompt)));
+        refresh_tray_menu(&app_handle);
+    }
+
+    apply_usage_stats(&data_dir, std::slice::from_mut(&mut prompt));
+    Ok(prompt)
+}
+
+/// 设置目录内的手动顺序；返回按新顺序排列的该目录全部 prompt
+#[tauri::command]
+pub async fn reorder_prompts(
+    app_handle: AppHandle,
+    folder: String,
+    ids: Vec<String>,
+) -> Result<Vec<PromptItem>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let mut prompts = apply_order(&data_dir, &folder, &ids).map_err(|e| e.to_string())?;
+
+    auto_commit(&app_handle, &data_dir, &format!("Reorder prompts in {}", folder));
+    refresh_tray_menu(&app_handle);
+
+    apply_usage_stats(&data_dir, &mut prompts);
+    Ok(prompts)
+}
@@ -0,0 +1,653 @@
+use super::file::{ensure_directories, load_config_sync, refresh_prompt_hotkeys, sanitize_filename};
+use super::git::auto_commit;
+use super::revision::record_revision;
+use super::trash::move_to_trash;
+use crate::models::{
+    HistoryConfig, InstalledPack, PackChangeReport, PackManifest, PackSource, PackState, PromptItem, TemplatePackInfo,
+};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{
+    content_hash, current_language, generate_timestamp, parse_markdown_prompt, serialize_markdown_prompt, write_atomic,
+    AppError, Language,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const PACK_STATE_FILE: &str = ".packs.json";
+const LOCAL_PACKS_DIR: &str = ".packs";
+const PACK_FOLDER: &str = "templates";
+
+/// 新数据目录首次初始化时安装的包
+const DEFAULT_PACKS: [&str; 1] = ["starter"];
+
+/// (manifest.json, [(语言, [(条目 id, markdown 原文)])])
+type BuiltinPack = (&'static str, &'static [(&'static str, &'static [(&'static str, &'static str)])]);
+
+macro_rules! starter_variant {
+    ($lang:literal) => {
+        (
+            $lang,
+            &[
+                starter_variant!(@item $lang, "starter-engineering-workflow"),
+                starter_variant!(@item $lang, "starter-bug-debugging"),
+                starter_variant!(@item $lang, "starter-prd-breakdown"),
+                starter_variant!(@item $lang, "starter-gemini-official-template"),
+                starter_variant!(@item $lang, "starter-claude-code-workflow"),
+                starter_variant!(@item $lang, "starter-openai-gpt52-official-template"),
+            ],
+        )
+    };
+    (@item $lang:literal, $id:literal) => {
+        ($id, include_str!(concat!("../../packs/starter/", $lang, "/", $id, ".md")))
+    };
+}
+
+const BUILTIN_PACKS: [BuiltinPack; 1] = [(
+    include_str!("../../packs/starter/manifest.json"),
+    &[starter_variant!("zh-CN"), starter_variant!("en")],
+)];
+
+struct Pack {
+    manifest: PackManifest,
+    source: PackSource,
+    /// 语言 -> 条目 id -> markdown 原文；单一版本的包语言为空串
+    files: HashMap<String, HashMap<String, String>>,
+}
+
+impl Pack {
+    /// 指定的语言必须存在；未指定时优先界面语言，没有对应版本则取第一个
+    fn variant(&self, requested: Option<&str>, preferred: Language) -> Result<Option<String>, AppError> {
+        let languages = &self.manifest.languages;
+        if languages.is_empty() {
+            return Ok(None);
+        }
+        match requested {
+            Some(language) if languages.iter().any(|l| l == language) => Ok(Some(language.to_string())),
+            Some(language) => Err(AppError::localized(
+                "VALIDATION_ERROR",
+                "pack_language_missing",
+                &[&self.manifest.id, &language],
+            )),
+            None => Ok(languages
+                .iter()
+                .find(|l| Language::from_tag(l) == preferred)
+                .or(languages.first())
+                .cloned()),
+        }
+    }
+
+    fn item(&self, data_dir: &Path, language: Option<&str>, id: &str) -> Result<PromptItem, AppError> {
+        let text = self
+            .files
+            .get(language.unwrap_or_default())
+            .and_then(|files| files.get(id))
+            .ok_or_else(|| AppError::localized("NOT_FOUND", "pack_item_missing", &[&self.manifest.id, &id]))?;
+        let path = item_path(data_dir, id);
+        let mut prompt = parse_markdown_prompt(text, &path.to_string_lossy(), PACK_FOLDER)?;
+
+        let now = generate_timestamp();
+        prompt.use_count = 0;
+        prompt.last_used = None;
+        prompt.created_at = now.clone();
+        prompt.updated_at = now;
+        Ok(prompt)
+    }
+}
+
+fn state_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(PACK_STATE_FILE)
+}
+
+fn item_path(data_dir: &Path, id: &str) -> PathBuf {
+    data_dir.join(PACK_FOLDER).join(format!("{}.md", id))
+}
+
+fn load_state(data_dir: &Path) -> Result<PackState, AppError> {
+    let path = state_path(data_dir);
+    if !path.exists() {
+        return Ok(PackState::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+fn save_state(data_dir: &Path, state: &PackState) -> Result<(), AppError> {
+    write_atomic(&state_path(data_dir), serde_json::to_string_pretty(state)?)
+}
+
+/// 只覆盖包提供的字段；置顶、排序、快捷键等本地设置不算作改动
+fn fingerprint(prompt: &PromptItem) -> String {
+    let text = [
+        prompt.title.as_str(),
+        prompt.description.as_deref().unwrap_or_default(),
+        &prompt.tags.join(","),
+        &prompt.content,
+    ]
+    .join("\n");
+    format!("{:016x}", content_hash(&text))
+}
+
+fn current_fingerprint(data_dir: &Path, id: &str) -> Option<String> {
+    let path = item_path(data_dir, id);
+    let text = fs::read_to_string(&path).ok()?;
+    parse_markdown_prompt(&text, &path.to_string_lossy(), PACK_FOLDER)
+        .ok()
+        .map(|p| fingerprint(&p))
+}
+
+fn check_manifest(manifest: &PackManifest) -> Result<(), AppError> {
+    if manifest.id.is_empty() || sanitize_filename(&manifest.id) != manifest.id {
+        return Err(AppError::localized("PARSE_ERROR", "pack_id_invalid", &[&manifest.id]));
+    }
+    if let Some(id) = manifest.items.iter().find(|id| id.is_empty() || sanitize_filename(id) != **id) {
+        return Err(AppError::localized("PARSE_ERROR", "pack_item_invalid", &[&manifest.id, &id]));
+    }
+    let valid_language = |l: &String| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if let Some(language) = manifest.languages.iter().find(|l| !valid_language(l)) {
+        return Err(AppError::localized("PARSE_ERROR", "pack_language_invalid", &[&manifest.id, &language]));
+    }
+    Ok(())
+}
+
+fn builtin_packs() -> Result<Vec<Pack>, AppError> {
+    BUILTIN_PACKS
+        .iter()
+        .map(|(manifest, variants)| {
+            let manifest: PackManifest = serde_json::from_str(manifest)?;
+            check_manifest(&manifest)?;
+            let files = variants
+                .iter()
+                .map(|(language, files)| {
+                    let files = files.iter().map(|(id, text)| (id.to_string(), text.to_string())).collect();
+                    (language.to_string(), files)
+                })
+                .collect();
+            Ok(Pack {
+                manifest,
+                source: PackSource::Builtin,
+                files,
+            })
+        })
+        .collect()
+}
+
+fn load_local_pack(dir: &Path) -> Result<Pack, AppError> {
+    let manifest: PackManifest = serde_json::from_str(&fs::read_to_string(dir.join("manifest.json"))?)?;
+    check_manifest(&manifest)?;
+
+    let variants: Vec<String> = if manifest.languages.is_empty() {
+        vec![String::new()]
+    } else {
+        manifest.languages.clone()
+    };
+    let mut files = HashMap::new();
+    for language in variants {
+        let mut variant = HashMap::new();
+        for id in &manifest.items {
+            let path = dir.join(&language).join(format!("{}.md", id));
+            let text = fs::read_to_string(&path)
+                .map_err(|e| AppError::io_failed("read_file_failed", path.display(), e))?;
+            variant.insert(id.clone(), text);
+        }
+        files.insert(language, variant);
+    }
+
+    Ok(Pack {
+        manifest,
+        source: PackSource::Local,
+        files,
+    })
+}
+
+/// 内置包加上数据目录 `.packs/<name>/manifest.json` 中的本地包；同 id 取版本较高者
+fn available_packs(data_dir: &Path) -> Result<Vec<Pack>, AppError> {
+    let mut packs = builtin_packs()?;
+
+    let local_dir = data_dir.join(LOCAL_PACKS_DIR);
+    if local_dir.is_dir() {
+        for entry in fs::read_dir(&local_dir)?.flatten() {
+            let dir = entry.path();
+            if !dir.join("manifest.json").is_file() {
+                continue;
+            }
+            let pack = match load_local_pack(&dir) {
+                Ok(pack) => pack,
+                Err(e) => {
+                    eprintln!("[PromptFlow] Skipping template pack {}: {}", dir.display(), e);
+                    continue;
+                }
+            };
+            match packs.iter_mut().find(|p| p.manifest.id == pack.manifest.id) {
+                Some(existing) if existing.manifest.version < pack.manifest.version => *existing = pack,
+                Some(_) => {}
+                None => packs.push(pack),
+            }
+        }
+    }
+
+    packs.sort_by(|a, b| a.manifest.id.cmp(&b.manifest.id));
+    Ok(packs)
+}
+
+fn find_pack(data_dir: &Path, pack_id: &str) -> Result<Pack, AppError> {
+    available_packs(data_dir)?
+        .into_iter()
+        .find(|p| p.manifest.id == pack_id)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "pack_not_found", &[&pack_id]))
+}
+
+fn write_item(data_dir: &Path, prompt: &PromptItem, history: &HistoryConfig) -> Result<(), AppError> {
+    write_atomic(Path::new(&prompt.file_path), serialize_markdown_prompt(prompt))?;
+    if let Err(e) = record_revision(data_dir, prompt, history) {
+        eprintln!("[PromptFlow] Failed to record revision for {}: {}", prompt.id, e);
+    }
+    Ok(())
+}
+
+fn trash_item(data_dir: &Path, id: &str) -> Result<(), AppError> {
+    move_to_trash(data_dir, &item_path(data_dir, id), Some(PACK_FOLDER), Some(id), id)?;
+    Ok(())
+}
+
+/// 同步到包的最新版本：未改动的条目覆盖，改动过的保留，用户删除的不再恢复
+fn sync_pack(
+    data_dir: &Path,
+    pack: &Pack,
+    installed: &mut InstalledPack,
+    history: &HistoryConfig,
+    report: &mut PackChangeReport,
+) -> Result<(), AppError> {
+    for id in &pack.manifest.items {
+        let incoming = pack.item(data_dir, installed.language.as_deref(), id)?;
+        let incoming_fingerprint = fingerprint(&incoming);
+        let current = current_fingerprint(data_dir, id);
+        let exists = item_path(data_dir, id).exists();
+
+        match (installed.items.get(id).cloned(), current) {
+            (Some(_), None) if !exists => report.skipped.push(id.clone()),
+            (_, Some(current)) if current == incoming_fingerprint => {
+                installed.items.insert(id.clone(), incoming_fingerprint);
+            }
+            (Some(recorded), Some(current)) if current == recorded => {
+                let path = item_path(data_dir, id);
+                let existing = parse_markdown_prompt(&fs::read_to_string(&path)?, &path.to_string_lossy(), PACK_FOLDER)?;
+                let mut prompt = incoming;
+                prompt.created_at = existing.created_at;
+                prompt.hotkey = existing.hotkey;
+                prompt.pinned = existing.pinned;
+                prompt.sort_order = existing.sort_order;
+
+                write_item(data_dir, &prompt, history)?;
+                installed.items.insert(id.clone(), incoming_fingerprint);
+                report.updated.push(id.clone());
+            }
+            (None, None) if !exists => {
+                write_item(data_dir, &incoming, history)?;
+                installed.items.insert(id.clone(), incoming_fingerprint);
+                report.added.push(id.clone());
+            }
+            _ => report.kept.push(id.clone()),
+        }
+    }
+
+    let dropped: Vec<(String, String)> = installed
+        .items
+        .iter()
+        .filter(|(id, _)| !pack.manifest.items.contains(id))
+        .map(|(id, recorded)| (id.clone(), recorded.clone()))
+        .collect();
+    for (id, recorded) in dropped {
+        installed.items.remove(&id);
+        remove_if_unchanged(data_dir, &id, &recorded, report)?;
+    }
+
+    installed.version = pack.manifest.version;
+    Ok(())
+}
+
+fn remove_if_unchanged(data_dir: &Path, id: &str, recorded: &str, report: &mut PackChangeReport) -> Result<(), AppError> {
+    match current_fingerprint(data_dir, id) {
+        Some(current) if current == recorded => {
+            trash_item(data_dir, id)?;
+            report.removed.push(id.to_string());
+        }
+        Some(_) => report.kept.push(id.to_string()),
+        None => {}
+    }
+    Ok(())
+}
+
+fn install_pack(
+    data_dir: &Path,
+    pack_id: &str,
+    language: Option<&str>,
+    history: &HistoryConfig,
+) -> Result<PackChangeReport, AppError> {
+    let pack = find_pack(data_dir, pack_id)?;
+    let mut state = load_state(data_dir)?;
+    if state.packs.contains_key(pack_id) {
+        return Err(AppError::localized("VALIDATION_ERROR", "pack_installed", &[&pack_id]));
+    }
+
+    let mut report = PackChangeReport {
+        pack_id: pack_id.to_string(),
+        version: Some(pack.manifest.version),
+        ..Default::default()
+    };
+    let mut installed = InstalledPack {
+        language: pack.variant(language, current_language())?,
+        ..Default::default()
+    };
+    sync_pack(data_dir, &pack, &mut installed, history, &mut report)?;
+
+    state.packs.insert(pack_id.to_string(), installed);
+    save_state(data_dir, &state)?;
+    Ok(report)
+}
+
+/// 传入 `language` 时切换语言版本，未改动的条目会被替换为该语言
+fn update_pack(
+    data_dir: &Path,
+    pack_id: &str,
+    language: Option<&str>,
+    history: &HistoryConfig,
+) -> Result<PackChangeReport, AppError> {
+    let pack = find_pack(data_dir, pack_id)?;
+    let mut state = load_state(data_dir)?;
+    let installed = state
+        .packs
+        .get_mut(pack_id)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "pack_not_installed", &[&pack_id]))?;
+
+    let mut report = PackChangeReport {
+        pack_id: pack_id.to_string(),
+        version: Some(pack.manifest.version),
+        ..Default::default()
+    };
+    // 早期安装没有记录语言，当时只有第一个语言版本
+    let language = language
+        .map(str::to_string)
+        .or_else(|| installed.language.clone())
+        .or_else(|| pack.manifest.languages.first().cloned());
+    installed.language = pack.variant(language.as_deref(), current_language())?;
+    sync_pack(data_dir, &pack, installed, history, &mut report)?;
+
+    save_state(data_dir, &state)?;
+    Ok(report)
+}
+
+/// 卸载时只把未改动的条目移入回收站；不需要包仍然可用
+fn uninstall_pack(data_dir: &Path, pack_id: &str) -> Result<PackChangeReport, AppError> {
+    let mut state = load_state(data_dir)?;
+    let installed = state
+        .packs
+        .remove(pack_id)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "pack_not_installed", &[&pack_id]))?;
+
+    let mut report = PackChangeReport {
+        pack_id: pack_id.to_string(),
+        ..Default::default()
+    };
+    for (id, recorded) in &installed.items {
+        remove_if_unchanged(data_dir, id, recorded, &mut report)?;
+    }
+
+    save_state(data_dir, &state)?;
+    Ok(report)
+}
+
+/// 数据目录没有 `.packs.json` 时按 `language` 安装默认包；之后不再自动安装，删除的条目不会回来。
+/// 旧版本直接写入的入门模板会被认领为已安装，缺失的视为用户已删除
+pub(crate) fn install_default_packs(
+    data_dir: &Path,
+    language: Language,
+    history: &HistoryConfig,
+) -> Result<(), AppError> {
+    if state_path(data_dir).exists() {
+        return Ok(());
+    }
+
+    let mut state = PackState::default();
+    for pack in available_packs(data_dir)?
+        .into_iter()
+        .filter(|p| DEFAULT_PACKS.contains(&p.manifest.id.as_str()))
+    {
+        let legacy = pack.manifest.items.iter().any(|id| item_path(data_dir, id).exists());
+        let mut installed = InstalledPack {
+            language: pack.variant(None, language)?,
+            ..Default::default()
+        };
+        if legacy {
+            // 认领已有文件所对应的语言版本
+            if let Some(matched) = pack.manifest.languages.iter().find(|l| {
+                pack.manifest.items.iter().any(|id| {
+                    let expected = pack.item(data_dir, Some(l.as_str()), id).map(|p| fingerprint(&p)).ok();
+                    expected.is_some() && expected == current_fingerprint(data_dir, id)
+                })
+            }) {
+                installed.language = Some(matched.clone());
+            }
+            for id in &pack.manifest.items {
+                let item = pack.item(data_dir, installed.language.as_deref(), id)?;
+                installed.items.insert(id.clone(), fingerprint(&item));
+            }
+            installed.version = pack.manifest.version;
+        } else {
+            let mut report = PackChangeReport::default();
+            sync_pack(data_dir, &pack, &mut installed, history, &mut report)?;
+        }
+        state.packs.insert(pack.manifest.id.clone(), installed);
+    }
+
+    save_state(data_dir, &state)
+}
+
+fn finish_change(app_handle: &AppHandle, data_dir: &Path, report: &PackChangeReport, action: &str) {
+    let changed = !(report.added.is_empty() && report.updated.is_empty() && report.removed.is_empty());
+    auto_commit(app_handle, data_dir, &format!("{} template pack: {}", action, report.pack_id));
+    if changed {
+        refresh_prompt_hotkeys(app_handle);
+        refresh_tray_menu(app_handle);
+    }
+}
+
+#[tauri::command]
+pub async fn list_template_packs(app_handle: AppHandle) -> Result<Vec<TemplatePackInfo>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let state = load_state(&data_dir).map_err(|e| e.to_string())?;
+    let packs = available_packs(&data_dir).map_err(|e| e.to_string())?;
+
+    Ok(packs
+        .into_iter()
+        .map(|pack| {
+            let installed = state.packs.get(&pack.manifest.id);
+            let installed_version = installed.map(|p| p.version);
+            TemplatePackInfo {
+                update_available: installed_version.map(|v| v < pack.manifest.version).unwrap_or(false),
+                installed_version,
+                installed_language: installed.and_then(|p| p.language.clone()),
+                item_count: pack.manifest.items.len(),
+                languages: pack.manifest.languages,
+                source: pack.source,
+                id: pack.manifest.id,
+                name: pack.manifest.name,
+                description: pack.manifest.description,
+                version: pack.manifest.version,
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn install_template_pack(
+    app_handle: AppHandle,
+    id: String,
+    language: Option<String>,
+) -> Result<PackChangeReport, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let history = load_config_sync(&app_handle).map(|c| c.history).unwrap_or_default();
+    let report = install_pack(&data_dir, &id, language.as_deref(), &history).map_err(|e| e.to_string())?;
+    finish_change(&app_handle, &data_dir, &report, "Install");
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn update_template_pack(
+    app_handle: AppHandle,
+    id: String,
+    language: Option<String>,
+) -> Result<PackChangeReport, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let history = load_config_sync(&app_handle).map(|c| c.history).unwrap_or_default();
+    let report = update_pack(&data_dir, &id, language.as_deref(), &history).map_err(|e| e.to_string())?;
+    finish_change(&app_handle, &data_dir, &report, "Update");
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn uninstall_template_pack(app_handle: AppHandle, id: String) -> Result<PackChangeReport, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let report = uninstall_pack(&data_dir, &id).map_err(|e| e.to_string())?;
+    finish_change(&app_handle, &data_dir, &report, "Uninstall");
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("promptflow-packs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(PACK_FOLDER)).unwrap();
+        dir
+    }
+
+    fn local_pack(version: u32, items: &[(&str, &str)]) -> Pack {
+        let files = items
+            .iter()
+            .map(|(id, content)| (id.to_string(), format!("---\ntitle: \"{}\"\n---\n\n{}", id, content)))
+            .collect();
+        Pack {
+            manifest: PackManifest {
+                id: "local".to_string(),
+                name: "Local".to_string(),
+                description: None,
+                version,
+                languages: vec![],
+                items: items.iter().map(|(id, _)| id.to_string()).collect(),
+            },
+            source: PackSource::Local,
+            files: HashMap::from([(String::new(), files)]),
+        }
+    }
+
+    fn sync(data_dir: &Path, pack: &Pack, installed: &mut InstalledPack) -> PackChangeReport {
+        let mut report = PackChangeReport::default();
+        sync_pack(data_dir, pack, installed, &HistoryConfig::default(), &mut report).unwrap();
+        report
+    }
+
+    fn content(data_dir: &Path, id: &str) -> String {
+        let path = item_path(data_dir, id);
+        parse_markdown_prompt(&fs::read_to_string(&path).unwrap(), &path.to_string_lossy(), PACK_FOLDER)
+            .unwrap()
+            .content
+    }
+
+    fn edit(data_dir: &Path, id: &str, content: &str) {
+        let path = item_path(data_dir, id);
+        let mut prompt =
+            parse_markdown_prompt(&fs::read_to_string(&path).unwrap(), &path.to_string_lossy(), PACK_FOLDER).unwrap();
+        prompt.content = content.to_string();
+        fs::write(&path, serialize_markdown_prompt(&prompt)).unwrap();
+    }
+
+    #[test]
+    fn updates_untouched_items_and_keeps_edits_and_deletions() {
+        let data_dir = temp_dir("sync");
+        let mut installed = InstalledPack::default();
+        let report = sync(&data_dir, &local_pack(1, &[("a", "one"), ("b", "one"), ("c", "one")]), &mut installed);
+        assert_eq!(report.added, vec!["a", "b", "c"]);
+
+        edit(&data_dir, "b", "my version");
+        fs::remove_file(item_path(&data_dir, "c")).unwrap();
+
+        let report = sync(&data_dir, &local_pack(2, &[("a", "two"), ("b", "two"), ("c", "two")]), &mut installed);
+        assert_eq!(report.updated, vec!["a"]);
+        assert_eq!(report.kept, vec!["b"]);
+        assert_eq!(report.skipped, vec!["c"]);
+        assert_eq!(content(&data_dir, "a"), "two");
+        assert_eq!(content(&data_dir, "b"), "my version");
+        assert!(!item_path(&data_dir, "c").exists());
+        assert_eq!(installed.version, 2);
+
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn trashes_dropped_items_only_when_unchanged() {
+        let data_dir = temp_dir("dropped");
+        let mut installed = InstalledPack::default();
+        sync(&data_dir, &local_pack(1, &[("a", "one"), ("b", "one"), ("c", "one")]), &mut installed);
+        edit(&data_dir, "b", "my version");
+
+        let report = sync(&data_dir, &local_pack(2, &[("c", "one")]), &mut installed);
+        assert_eq!(report.removed, vec!["a"]);
+        assert_eq!(report.kept, vec!["b"]);
+        assert!(!item_path(&data_dir, "a").exists());
+        assert!(data_dir.join(".trash").is_dir());
+        assert_eq!(content(&data_dir, "b"), "my version");
+        assert_eq!(installed.items.keys().collect::<Vec<_>>(), vec!["c"]);
+
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn claims_legacy_starter_files() {
+        let data_dir = temp_dir("legacy");
+        let pack = find_pack(&data_dir, "starter").unwrap();
+        let id = pack.manifest.items[0].clone();
+        let legacy = pack.files["en"][&id].clone();
+        fs::write(item_path(&data_dir, &id), &legacy).unwrap();
+
+        install_default_packs(&data_dir, Language::ZhCn, &HistoryConfig::default()).unwrap();
+        let state = load_state(&data_dir).unwrap();
+        let installed = &state.packs["starter"];
+        assert_eq!(installed.language.as_deref(), Some("en"));
+        assert_eq!(installed.items.len(), pack.manifest.items.len());
+        assert_eq!(fs::read_to_string(item_path(&data_dir, &id)).unwrap(), legacy);
+
+        // 旧版本中缺失的条目视为用户已删除
+        let report = update_pack(&data_dir, "starter", None, &HistoryConfig::default()).unwrap();
+        assert_eq!(report.skipped.len(), pack.manifest.items.len() - 1);
+        assert!(report.added.is_empty());
+        assert_eq!(fs::read_dir(data_dir.join(PACK_FOLDER)).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn switches_language_and_uninstalls_untouched_items() {
+        let data_dir = temp_dir("language");
+        let history = HistoryConfig::default();
+        install_default_packs(&data_dir, Language::En, &history).unwrap();
+        let items = find_pack(&data_dir, "starter").unwrap().manifest.items;
+        edit(&data_dir, &items[0], "my version");
+
+        let report = update_pack(&data_dir, "starter", Some("zh-CN"), &history).unwrap();
+        assert_eq!(report.kept, vec![items[0].clone()]);
+        assert_eq!(report.updated.len(), items.len() - 1);
+        assert_eq!(load_state(&data_dir).unwrap().packs["starter"].language.as_deref(), Some("zh-CN"));
+
+        let report = uninstall_pack(&data_dir, "starter").unwrap();
+        assert_eq!(report.kept, vec![items[0].clone()]);
+        assert_eq!(report.removed.len(), items.len() - 1);
+        assert!(item_path(&data_dir, &items[0]).exists());
+        assert!(!item_path(&data_dir, &items[1]).exists());
+        assert!(load_state(&data_dir).unwrap().packs.is_empty());
+
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+}
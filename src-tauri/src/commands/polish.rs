@@ -0,0 +1,92 @@
+use super::file::ensure_directories;
+use super::usage::record_usage_event;
+use crate::models::{PolishRun, PolishRunInput, UsageAction};
+use crate::utils::{
+    append_json_line, diff_words, generate_id, generate_timestamp, read_json_lines, write_json_lines,
+    AppError, DiffSegment,
+};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const POLISH_HISTORY_FILE: &str = ".polish_history.jsonl";
+
+fn polish_history_path(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    let data_dir = ensure_directories(app_handle)?;
+    Ok(data_dir.join(POLISH_HISTORY_FILE))
+}
+
+fn find_polish_run(app_handle: &AppHandle, id: &str) -> Result<PolishRun, AppError> {
+    let path = polish_history_path(app_handle)?;
+    read_json_lines::<PolishRun>(&path)?
+        .into_iter()
+        .find(|run| run.id == id)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "polish_run_not_found", &[&id]))
+}
+
+#[tauri::command]
+pub async fn record_polish_run(app_handle: AppHandle, run: PolishRunInput) -> Result<PolishRun, String> {
+    let path = polish_history_path(&app_handle).map_err(|e| e.to_string())?;
+
+    if let (Some(prompt_id), Some(folder)) = (&run.prompt_id, &run.folder) {
+        let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+        if let Err(e) = record_usage_event(&data_dir, prompt_id, folder, UsageAction::Polish, Some(run.preset.clone())) {
+            eprintln!("[PromptFlow] Failed to record usage for {}: {}", prompt_id, e);
+        }
+    }
+
+    let run = PolishRun {
+        id: generate_id(),
+        input: run.input,
+        output: run.output,
+        preset: run.preset,
+        model: run.model,
+        created_at: generate_timestamp(),
+        usage: run.usage,
+    };
+
+    append_json_line(&path, &run).map_err(|e| e.to_string())?;
+    Ok(run)
+}
+
+#[tauri::command]
+pub async fn list_polish_history(app_handle: AppHandle, limit: Option<usize>) -> Result<Vec<PolishRun>, String> {
+    let path = polish_history_path(&app_handle).map_err(|e| e.to_string())?;
+    let mut runs: Vec<PolishRun> = read_json_lines(&path).map_err(|e| e.to_string())?;
+
+    // 日志按追加顺序存储，返回时最新的在前
+    runs.reverse();
+    if let Some(limit) = limit {
+        runs.truncate(limit);
+    }
+
+    Ok(runs)
+}
+
+#[tauri::command]
+pub async fn get_polish_run(app_handle: AppHandle, id: String) -> Result<PolishRun, String> {
+    find_polish_run(&app_handle, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_polish_history(app_handle: AppHandle, id: Option<String>) -> Result<(), String> {
+    let path = polish_history_path(&app_handle).map_err(|e| e.to_string())?;
+
+    let Some(id) = id else {
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| AppError::io_failed("delete_file_failed", path.display(), e).to_string())?;
+        }
+        return Ok(());
+    };
+
+    let runs: Vec<PolishRun> = read_json_lines(&path).map_err(|e| e.to_string())?;
+    let remaining: Vec<PolishRun> = runs.into_iter().filter(|run| run.id != id).collect();
+    write_json_lines(&path, &remaining).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn diff_polish_run(app_handle: AppHandle, id: String) -> Result<Vec<DiffSegment>, String> {
+    let run = find_polish_run(&app_handle, &id).map_err(|e| e.to_string())?;
+    Ok(diff_words(&run.input, &run.output))
+}
@@ -0,0 +1,358 @@
+use super::file::{ensure_directories, load_all_prompts};
+use super::repository::{load_all_repositories, load_shared_layers, tag_active_repository};
+use super::usage::apply_usage_stats;
+use crate::models::{
+    DateRange, PromptFilter, PromptItem, PromptPage, PromptQuery, SortDirection, SortField, TagMatch,
+};
+use crate::utils::{extract_variables, relevance_score, AppError};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use tauri::AppHandle;
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+impl SortKey {
+    fn compare(&self, other: &SortKey) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            (SortKey::Number(_), SortKey::Text(_)) => Ordering::Less,
+            (SortKey::Text(_), SortKey::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// 游标记录上一页最后一项的排序键，下一页从严格排在它之后的项开始，
+/// 因此翻页期间增删 prompt 不会导致重复或遗漏
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Cursor {
+    key: SortKey,
+    #[serde(default)]
+    repository: String,
+    folder: String,
+    id: String,
+}
+
+struct Candidate {
+    key: SortKey,
+    prompt: PromptItem,
+}
+
+fn in_range(value: Option<&str>, range: &Option<DateRange>) -> bool {
+    let Some(range) = range else {
+        return true;
+    };
+    let Some(value) = value else {
+        return range.after.is_none() && range.before.is_none();
+    };
+
+    range.after.as_deref().map(|after| value > after).unwrap_or(true)
+        && range.before.as_deref().map(|before| value < before).unwrap_or(true)
+}
+
+fn matches_filter(prompt: &PromptItem, filter: &PromptFilter) -> bool {
+    if !filter.folders.is_empty() && !filter.folders.contains(&prompt.folder) {
+        return false;
+    }
+
+    if !filter.tags.is_empty() {
+        let tag_hit = |tag: &String| prompt.tags.contains(tag);
+        let ok = match filter.tag_match {
+            TagMatch::Any => filter.tags.iter().any(tag_hit),
+            TagMatch::All => filter.tags.iter().all(tag_hit),
+        };
+        if !ok {
+            return false;
+        }
+    }
+
+    if filter.min_use_count.map(|min| prompt.use_count < min).unwrap_or(false) {
+        return false;
+    }
+    if filter.pinned.map(|pinned| prompt.pinned != pinned).unwrap_or(false) {
+        return false;
+    }
+    if let Some(has_variables) = filter.has_variables {
+        if extract_variables(&prompt.content).is_empty() == has_variables {
+            return false;
+        }
+    }
+
+    in_range(Some(&prompt.created_at), &filter.created)
+        && in_range(Some(&prompt.updated_at), &filter.updated)
+        && in_range(prompt.last_used.as_deref(), &filter.last_used)
+}
+
+fn sort_key(prompt: &PromptItem, field: SortField, relevance: Option<f64>) -> SortKey {
+    match field {
+        SortField::Title => SortKey::Text(prompt.title.to_lowercase()),
+        SortField::Created => SortKey::Text(prompt.created_at.clone()),
+        SortField::Updated => SortKey::Text(prompt.updated_at.clone()),
+        // 从未使用的视为最早使用：降序时排在最后，升序时排在最前
+        SortField::LastUsed => SortKey::Text(prompt.last_used.clone().unwrap_or_default()),
+        SortField::UseCount => SortKey::Number(f64::from(prompt.use_count)),
+        SortField::Relevance => SortKey::Number(relevance.unwrap_or(0.0)),
+        // 编码为单个数值：置顶占高位，未排序的排在所有已排序项之后
+        SortField::Manual => {
+            let rank = if prompt.pinned { 0.0 } else { 2f64.powi(33) };
+            let order = prompt.sort_order.map(f64::from).unwrap_or(2f64.powi(32));
+            SortKey::Number(rank + order)
+        }
+    }
+}
+
+fn compare(a: (&SortKey, [&str; 3]), b: (&SortKey, [&str; 3]), direction: SortDirection) -> Ordering {
+    let by_key = match direction {
+        SortDirection::Asc => a.0.compare(b.0),
+        SortDirection::Desc => b.0.compare(a.0),
+    };
+    // 仓库/folder/id 保证全序，游标才能唯一定位
+    by_key.then_with(|| a.1.cmp(&b.1))
+}
+
+fn position(candidate: &Candidate) -> (&SortKey, [&str; 3]) {
+    let prompt = &candidate.prompt;
+    (
+        &candidate.key,
+        [prompt.repository.as_deref().unwrap_or_default(), &prompt.folder, &prompt.id],
+    )
+}
+
+fn run_query(prompts: Vec<PromptItem>, query: &PromptQuery) -> Result<PromptPage, AppError> {
+    let search = query.filter.search.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let field = query.sort.field;
+    if field == SortField::Relevance && search.is_none() {
+        return Err(AppError::localized("VALIDATION_ERROR", "relevance_needs_search", &[]));
+    }
+    let direction = query.sort.direction.unwrap_or(match field {
+        SortField::Title | SortField::Manual => SortDirection::Asc,
+        _ => SortDirection::Desc,
+    });
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let cursor: Option<Cursor> = query
+        .cursor
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|_| AppError::localized("VALIDATION_ERROR", "invalid_cursor", &[]))?;
+
+    let mut candidates: Vec<Candidate> = prompts
+        .into_iter()
+        .filter(|p| matches_filter(p, &query.filter))
+        .filter_map(|prompt| {
+            let relevance = match search {
+                Some(search) => Some(relevance_score(&prompt, search)?),
+                None => None,
+            };
+            Some(Candidate {
+                key: sort_key(&prompt, field, relevance),
+                prompt,
+            })
+        })
+        .collect();
+    let total = candidates.len();
+
+    candidates.sort_by(|a, b| compare(position(a), position(b), direction));
+
+    let start = match cursor {
+        Some(ref cursor) => candidates.partition_point(|c| {
+            let at = (&cursor.key, [cursor.repository.as_str(), &cursor.folder, &cursor.id]);
+            compare(position(c), at, direction) != Ordering::Greater
+        }),
+        None => 0,
+    };
+
+    let page: Vec<Candidate> = candidates.into_iter().skip(start).take(limit + 1).collect();
+    let has_more = page.len() > limit;
+    let mut items = vec![];
+    let mut next_cursor = None;
+    for (i, candidate) in page.into_iter().take(limit).enumerate() {
+        if has_more && i == limit - 1 {
+            let cursor = Cursor {
+                key: candidate.key,
+                repository: candidate.prompt.repository.clone().unwrap_or_default(),
+                folder: candidate.prompt.folder.clone(),
+                id: candidate.prompt.id.clone(),
+            };
+            next_cursor = Some(serde_json::to_string(&cursor)?);
+        }
+        items.push(candidate.prompt);
+    }
+
+    Ok(PromptPage {
+        items,
+        next_cursor,
+        total,
+    })
+}
+
+#[tauri::command]
+pub async fn query_prompts(app_handle: AppHandle, query: Option<PromptQuery>) -> Result<PromptPage, String> {
+    let query = query.unwrap_or_default();
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let prompts = if query.filter.all_repositories {
+        load_all_repositories(&app_handle, &data_dir).map_err(|e| e.to_string())?
+    } else {
+        let mut prompts = load_all_prompts(&data_dir, true).map_err(|e| e.to_string())?;
+        apply_usage_stats(&data_dir, &mut prompts);
+        tag_active_repository(&app_handle, &data_dir, &mut prompts);
+        prompts.extend(load_shared_layers(&app_handle, &data_dir, None));
+        prompts
+    };
+
+    run_query(prompts, &query).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PromptSort, TagMatch};
+
+    fn prompt(id: &str, tags: &[&str], updated_at: &str, last_used: Option<&str>) -> PromptItem {
+        PromptItem {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            description: None,
+            use_count: 0,
+            last_used: last_used.map(|l| l.to_string()),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: updated_at.to_string(),
+            file_path: format!("favorites/{}.md", id),
+            folder: "favorites".to_string(),
+            hotkey: None,
+            pinned: false,
+            sort_order: None,
+            repository: None,
+            read_only: false,
+            forked_from: None,
+        }
+    }
+
+    fn library() -> Vec<PromptItem> {
+        (0..7)
+            .map(|i| prompt(&format!("p{}", i), &[], &format!("2024-03-0{}T00:00:00Z", i + 1), None))
+            .collect()
+    }
+
+    fn ids(page: &PromptPage) -> Vec<&str> {
+        page.items.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    fn filtered(prompts: Vec<PromptItem>, filter: PromptFilter) -> Vec<String> {
+        let query = PromptQuery {
+            filter,
+            sort: PromptSort {
+                field: SortField::Title,
+                direction: None,
+            },
+            ..Default::default()
+        };
+        run_query(prompts, &query).unwrap().items.into_iter().map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn pages_with_cursor_stay_stable_across_inserts() {
+        let mut query = PromptQuery {
+            limit: Some(3),
+            ..Default::default()
+        };
+        let first = run_query(library(), &query).unwrap();
+        assert_eq!(ids(&first), ["p6", "p5", "p4"]);
+        assert_eq!(first.total, 7);
+
+        // 翻页之间新增的 prompt 排在游标之前（更新时间更晚）或之后，都不会造成重复或遗漏
+        let mut changed = library();
+        changed.push(prompt("new-top", &[], "2024-03-09T00:00:00Z", None));
+        changed.push(prompt("p3b", &[], "2024-03-04T00:00:00Z", None));
+        query.cursor = first.next_cursor.clone();
+        let second = run_query(changed.clone(), &query).unwrap();
+        assert_eq!(ids(&second), ["p3", "p3b", "p2"]);
+
+        query.cursor = second.next_cursor.clone();
+        let third = run_query(changed, &query).unwrap();
+        assert_eq!(ids(&third), ["p1", "p0"]);
+        assert!(third.next_cursor.is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        let query = PromptQuery {
+            cursor: Some("not a cursor".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(run_query(library(), &query).unwrap_err().key, Some("invalid_cursor"));
+    }
+
+    #[test]
+    fn matches_any_or_all_tags() {
+        let prompts = vec![
+            prompt("a", &["rust", "review"], "2024-01-01T00:00:00Z", None),
+            prompt("b", &["rust"], "2024-01-01T00:00:00Z", None),
+            prompt("c", &["go"], "2024-01-01T00:00:00Z", None),
+        ];
+        let tags = vec!["rust".to_string(), "review".to_string()];
+
+        let any = PromptFilter {
+            tags: tags.clone(),
+            ..Default::default()
+        };
+        assert_eq!(filtered(prompts.clone(), any), ["a", "b"]);
+
+        let all = PromptFilter {
+            tags,
+            tag_match: TagMatch::All,
+            ..Default::default()
+        };
+        assert_eq!(filtered(prompts, all), ["a"]);
+    }
+
+    #[test]
+    fn filters_open_date_intervals() {
+        let prompts = vec![
+            prompt("a", &[], "2024-01-01T00:00:00Z", Some("2024-05-01T00:00:00Z")),
+            prompt("b", &[], "2024-02-01T00:00:00Z", Some("2024-06-01T00:00:00Z")),
+            prompt("c", &[], "2024-03-01T00:00:00Z", None),
+        ];
+        let updated = |after: Option<&str>, before: Option<&str>| PromptFilter {
+            updated: Some(DateRange {
+                after: after.map(|a| a.to_string()),
+                before: before.map(|b| b.to_string()),
+            }),
+            ..Default::default()
+        };
+
+        // 开区间：边界本身不包含在内
+        assert_eq!(filtered(prompts.clone(), updated(Some("2024-01-01T00:00:00Z"), None)), ["b", "c"]);
+        assert_eq!(filtered(prompts.clone(), updated(None, Some("2024-03-01T00:00:00Z"))), ["a", "b"]);
+        assert_eq!(
+            filtered(prompts.clone(), updated(Some("2024-01-01T00:00:00Z"), Some("2024-03-01T00:00:00Z"))),
+            ["b"]
+        );
+
+        // 从未使用的 prompt 只在没有给出边界时匹配
+        let last_used = PromptFilter {
+            last_used: Some(DateRange {
+                after: Some("2024-05-15T00:00:00Z".to_string()),
+                before: None,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(filtered(prompts.clone(), last_used), ["b"]);
+        let unbounded = PromptFilter {
+            last_used: Some(DateRange::default()),
+            ..Default::default()
+        };
+        assert_eq!(filtered(prompts, unbounded), ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,408 @@
+use super::file::{
+    check_prompt_ref, default_data_dir, ensure_directories, load_all_prompts, load_config_sync, load_folder_prompts,
+    prepare_data_dir, read_prompt, record_prompt_revision, refresh_prompt_hotkeys, resolve_storage_path,
+    sanitize_filename, save_config_sync, PROMPT_FOLDERS,
+};
+use super::git::auto_commit;
+use super::usage::apply_usage_stats;
+use crate::models::{AppConfig, PromptItem, RepositoryConfig, RepositoryInfo};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{generate_timestamp, serialize_markdown_prompt, write_atomic, AppError};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// 仓库的目录；路径无效时返回 None
+fn repository_dir(repository: &RepositoryConfig, default_dir: &Path) -> Option<PathBuf> {
+    resolve_storage_path(&repository.path, default_dir.to_path_buf()).ok()
+}
+
+fn unique_id(config: &AppConfig, name: &str) -> String {
+    let base = match sanitize_filename(name) {
+        id if id.is_empty() => "repository".to_string(),
+        id => id,
+    };
+    let mut id = base.clone();
+    let mut n = 2;
+    while config.repositories.iter().any(|r| r.id == id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+/// 当前数据目录对应的已登记仓库 id
+pub(crate) fn active_repository_id(config: &AppConfig, data_dir: &Path, default_dir: &Path) -> Option<String> {
+    config
+        .repositories
+        .iter()
+        .find(|r| repository_dir(r, default_dir).as_deref() == Some(data_dir))
+        .map(|r| r.id.clone())
+}
+
+pub(crate) fn tag_repository(prompts: &mut [PromptItem], repository: Option<&str>) {
+    for prompt in prompts.iter_mut() {
+        prompt.repository = repository.map(|r| r.to_string());
+    }
+}
+
+/// 给当前仓库加载的 prompt 标注仓库 id
+pub(crate) fn tag_active_repository(app_handle: &AppHandle, data_dir: &Path, prompts: &mut [PromptItem]) {
+    let (Ok(config), Ok(default_dir)) = (load_config_sync(app_handle), default_data_dir(app_handle)) else {
+        return;
+    };
+    tag_repository(prompts, active_repository_id(&config, data_dir, &default_dir).as_deref());
+}
+
+/// 从所有可用仓库加载 prompt，各自叠加使用统计；不可用的仓库跳过
+pub(crate) fn load_all_repositories(app_handle: &AppHandle, data_dir: &Path) -> Result<Vec<PromptItem>, AppError> {
+    let config = load_config_sync(app_handle)?;
+    let default_dir = default_data_dir(app_handle)?;
+
+    let mut dirs: Vec<(Option<String>, PathBuf, bool)> = config
+        .repositories
+        .iter()
+        .filter_map(|r| repository_dir(r, &default_dir).map(|dir| (Some(r.id.clone()), dir, r.read_only)))
+        .filter(|(_, dir, _)| dir.is_dir())
+        .collect();
+    if !dirs.iter().any(|(_, dir, _)| dir == data_dir) {
+        dirs.insert(0, (None, data_dir.to_path_buf(), false));
+    }
+
+    let mut prompts = vec![];
+    for (id, dir, read_only) in dirs {
+        let mut loaded = load_all_prompts(&dir, false)?;
+        apply_usage_stats(&dir, &mut loaded);
+        tag_repository(&mut loaded, id.as_deref());
+        for prompt in loaded.iter_mut() {
+            prompt.read_only = read_only;
+        }
+        prompts.extend(loaded);
+    }
+    Ok(prompts)
+}
+
+/// 除当前仓库外所有可用的只读仓库，它们作为共享层叠加在个人库之上
+fn shared_layers(config: &AppConfig, data_dir: &Path, default_dir: &Path) -> Vec<(RepositoryConfig, PathBuf)> {
+    config
+        .repositories
+        .iter()
+        .filter(|r| r.read_only)
+        .filter_map(|r| repository_dir(r, default_dir).map(|dir| (r.clone(), dir)))
+        .filter(|(_, dir)| dir != data_dir && dir.is_dir())
+        .collect()
+}
+
+/// 加载共享层中的 prompt（`folder` 为 None 时加载全部目录），标记为只读；
+/// 共享目录可能是网络路径，读取失败的层只记录日志，不影响个人库
+pub(crate) fn load_shared_layers(app_handle: &AppHandle, data_dir: &Path, folder: Option<&str>) -> Vec<PromptItem> {
+    let (Ok(config), Ok(default_dir)) = (load_config_sync(app_handle), default_data_dir(app_handle)) else {
+        return vec![];
+    };
+
+    let mut prompts = vec![];
+    for (repository, dir) in shared_layers(&config, data_dir, &default_dir) {
+        let loaded = match folder {
+            Some(folder) => load_folder_prompts(&dir, folder),
+            None => load_all_prompts(&dir, false),
+        };
+        let mut loaded = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("[PromptFlow] Failed to load shared repository {}: {}", repository.id, e);
+                continue;
+            }
+        };
+        apply_usage_stats(&dir, &mut loaded);
+        tag_repository(&mut loaded, Some(&repository.id));
+        for prompt in loaded.iter_mut() {
+            prompt.read_only = true;
+        }
+        prompts.extend(loaded);
+    }
+    prompts
+}
+
+/// 在共享层中查找 prompt；指定 `repository` 时只查该仓库
+fn find_shared_prompt(
+    app_handle: &AppHandle,
+    data_dir: &Path,
+    repository: Option<&str>,
+    folder: &str,
+    id: &str,
+) -> Result<Option<(RepositoryConfig, PromptItem)>, AppError> {
+    check_prompt_ref(folder, id)?;
+    // 保险库只属于个人库，不参与共享
+    if !PROMPT_FOLDERS.contains(&folder) {
+        return Ok(None);
+    }
+    let config = load_config_sync(app_handle)?;
+    let default_dir = default_data_dir(app_handle)?;
+
+    for (layer, dir) in shared_layers(&config, data_dir, &default_dir) {
+        if repository.is_some_and(|r| r != layer.id) {
+            continue;
+        }
+        if let Ok(mut prompt) = read_prompt(&dir, folder, id) {
+            tag_repository(std::slice::from_mut(&mut prompt), Some(&layer.id));
+            prompt.read_only = true;
+            return Ok(Some((layer, prompt)));
+        }
+    }
+    Ok(None)
+}
+
+/// 修改前检查目标是否属于只读仓库：显式指定了只读仓库，
+/// 或者个人库中没有该 prompt 而共享层中有，都返回 PERMISSION_DENIED；
+/// 修改总是写入当前数据目录，显式指定的仓库必须是当前仓库
+pub(crate) fn ensure_writable(
+    app_handle: &AppHandle,
+    data_dir: &Path,
+    repository: Option<&str>,
+    folder: &str,
+    id: &str,
+) -> Result<(), AppError> {
+    check_prompt_ref(folder, id)?;
+    let target = format!("{}/{}", folder, id);
+    if let Some(repository) = repository {
+        let config = load_config_sync(app_handle)?;
+        let r = find_repository(&config, repository)?;
+        if r.read_only {
+            return Err(AppError::localized("PERMISSION_DENIED", "prompt_read_only", &[&target, &r.name]));
+        }
+        let default_dir = default_data_dir(app_handle)?;
+        if active_repository_id(&config, data_dir, &default_dir).as_deref() != Some(repository) {
+            return Err(AppError::localized("VALIDATION_ERROR", "repository_not_active", &[&r.name]));
+        }
+        return Ok(());
+    }
+
+    if data_dir.join(folder).join(format!("{}.md", id)).is_file() {
+        return Ok(());
+    }
+    match find_shared_prompt(app_handle, data_dir, None, folder, id)? {
+        Some((layer, _)) => Err(AppError::localized("PERMISSION_DENIED", "prompt_read_only", &[&target, &layer.name])),
+        None => Ok(()),
+    }
+}
+
+/// 个人库中没有时回退到共享层，用于只读访问
+pub(crate) fn read_layered_prompt(
+    app_handle: &AppHandle,
+    data_dir: &Path,
+    folder: &str,
+    id: &str,
+) -> Result<(PromptItem, bool), AppError> {
+    check_prompt_ref(folder, id)?;
+    match read_prompt(data_dir, folder, id) {
+        Ok(prompt) => Ok((prompt, false)),
+        Err(e) => match find_shared_prompt(app_handle, data_dir, None, folder, id)? {
+            Some((_, prompt)) => Ok((prompt, true)),
+            None => Err(e),
+        },
+    }
+}
+
+/// 读取配置，并在当前数据目录尚未登记时把它登记为仓库
+fn load_registered(app_handle: &AppHandle) -> Result<(AppConfig, PathBuf, PathBuf), AppError> {
+    let mut config = load_config_sync(app_handle)?;
+    let default_dir = default_data_dir(app_handle)?;
+    let data_dir = resolve_storage_path(&config.storage.path, default_dir.clone())?;
+
+    if active_repository_id(&config, &data_dir, &default_dir).is_none() {
+        let name = data_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "PromptFlow".to_string());
+        config.repositories.insert(
+            0,
+            RepositoryConfig {
+                id: unique_id(&config, &name),
+                name,
+                path: data_dir.to_string_lossy().to_string(),
+                read_only: false,
+            },
+        );
+        save_config_sync(app_handle, &config)?;
+    }
+
+    Ok((config, data_dir, default_dir))
+}
+
+fn repository_info(repository: &RepositoryConfig, data_dir: &Path, default_dir: &Path) -> RepositoryInfo {
+    let dir = repository_dir(repository, default_dir);
+    RepositoryInfo {
+        id: repository.id.clone(),
+        name: repository.name.clone(),
+        path: repository.path.clone(),
+        read_only: repository.read_only,
+        active: dir.as_deref() == Some(data_dir),
+        available: dir.map(|d| d.is_dir()).unwrap_or(false),
+    }
+}
+
+fn find_repository<'a>(config: &'a AppConfig, id: &str) -> Result<&'a RepositoryConfig, AppError> {
+    config
+        .repositories
+        .iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "repository_not_found", &[&id]))
+}
+
+#[tauri::command]
+pub async fn list_repositories(app_handle: AppHandle) -> Result<Vec<RepositoryInfo>, String> {
+    let (config, data_dir, default_dir) = load_registered(&app_handle).map_err(|e| e.to_string())?;
+    Ok(config
+        .repositories
+        .iter()
+        .map(|r| repository_info(r, &data_dir, &default_dir))
+        .collect())
+}
+
+/// 登记一个仓库；可写仓库的目录不存在时会被创建，只读仓库必须已经存在
+#[tauri::command]
+pub async fn add_repository(
+    app_handle: AppHandle,
+    name: String,
+    path: String,
+    read_only: Option<bool>,
+) -> Result<RepositoryInfo, String> {
+    let (mut config, data_dir, default_dir) = load_registered(&app_handle).map_err(|e| e.to_string())?;
+    let read_only = read_only.unwrap_or(false);
+
+    let dir = resolve_storage_path(&path, PathBuf::new()).map_err(|e| e.to_string())?;
+    if dir.components().next().is_none() {
+        return Err(AppError::localized("VALIDATION_ERROR", "data_dir_empty", &[]).to_string());
+    }
+    if config
+        .repositories
+        .iter()
+        .any(|r| repository_dir(r, &default_dir).as_deref() == Some(dir.as_path()))
+    {
+        return Err(AppError::localized("VALIDATION_ERROR", "repository_exists", &[&dir.display()]).to_string());
+    }
+    if read_only {
+        if !dir.is_dir() {
+            return Err(AppError::localized("NOT_FOUND", "repository_unavailable", &[&dir.display()]).to_string());
+        }
+    } else {
+        prepare_data_dir(dir.clone(), &config.history).map_err(|e| e.to_string())?;
+    }
+
+    let name = match name.trim() {
+        "" => dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone()),
+        name => name.to_string(),
+    };
+    let repository = RepositoryConfig {
+        id: unique_id(&config, &name),
+        name,
+        path: dir.to_string_lossy().to_string(),
+        read_only,
+    };
+    let info = repository_info(&repository, &data_dir, &default_dir);
+    config.repositories.push(repository);
+    save_config_sync(&app_handle, &config).map_err(|e| e.to_string())?;
+
+    Ok(info)
+}
+
+/// 只取消登记，不删除目录中的文件
+#[tauri::command]
+pub async fn remove_repository(app_handle: AppHandle, id: String) -> Result<(), String> {
+    let (mut config, data_dir, default_dir) = load_registered(&app_handle).map_err(|e| e.to_string())?;
+    let repository = find_repository(&config, &id).map_err(|e| e.to_string())?;
+    if repository_info(repository, &data_dir, &default_dir).active {
+        return Err(AppError::localized("VALIDATION_ERROR", "repository_active", &[]).to_string());
+    }
+
+    config.repositories.retain(|r| r.id != id);
+    save_config_sync(&app_handle, &config).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn switch_repository(app_handle: AppHandle, id: String) -> Result<RepositoryInfo, String> {
+    let (mut config, _, default_dir) = load_registered(&app_handle).map_err(|e| e.to_string())?;
+    let repository = find_repository(&config, &id).map_err(|e| e.to_string())?.clone();
+    if repository.read_only {
+        return Err(AppError::localized("VALIDATION_ERROR", "repository_read_only", &[&repository.name]).to_string());
+    }
+
+    let dir = repository_dir(&repository, &default_dir)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "repository_unavailable", &[&repository.path]).to_string())?;
+    let data_dir = prepare_data_dir(dir, &config.history).map_err(|e| e.to_string())?;
+
+    config.storage.path = repository.path.clone();
+    save_config_sync(&app_handle, &config).map_err(|e| e.to_string())?;
+
+    refresh_prompt_hotkeys(&app_handle);
+    refresh_tray_menu(&app_handle);
+
+    let info = repository_info(&repository, &data_dir, &default_dir);
+    let _ = app_handle.emit("repository-switched", &info);
+    Ok(info)
+}
+
+/// 把共享仓库中的 prompt 复制到个人库的 favorites，`forked_from` 指回原 prompt；
+/// 快捷键不随之复制，id 冲突时追加数字后缀
+#[tauri::command]
+pub async fn fork_prompt(
+    app_handle: AppHandle,
+    repository: String,
+    folder: String,
+    id: String,
+) -> Result<PromptItem, String> {
+    if !PROMPT_FOLDERS.contains(&folder.as_str()) {
+        return Err(AppError::localized("VALIDATION_ERROR", "unknown_folder", &[&folder]).to_string());
+    }
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let config = load_config_sync(&app_handle).map_err(|e| e.to_string())?;
+    let default_dir = default_data_dir(&app_handle).map_err(|e| e.to_string())?;
+    find_repository(&config, &repository).map_err(|e| e.to_string())?;
+
+    let (_, source) = find_shared_prompt(&app_handle, &data_dir, Some(&repository), &folder, &id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| {
+            AppError::localized("NOT_FOUND", "prompt_not_found", &[&format!("{}/{}/{}", repository, folder, id)])
+                .to_string()
+        })?;
+
+    let favorites = data_dir.join("favorites");
+    let base = match sanitize_filename(&source.id) {
+        base if base.is_empty() => "prompt".to_string(),
+        base => base,
+    };
+    let mut new_id = base.clone();
+    let mut n = 2;
+    while favorites.join(format!("{}.md", new_id)).exists() {
+        new_id = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let now = generate_timestamp();
+    let file_path = favorites.join(format!("{}.md", new_id));
+    let prompt = PromptItem {
+        id: new_id,
+        use_count: 0,
+        last_used: None,
+        created_at: now.clone(),
+        updated_at: now,
+        file_path: file_path.to_string_lossy().to_string(),
+        folder: "favorites".to_string(),
+        hotkey: None,
+        pinned: false,
+        sort_order: None,
+        repository: active_repository_id(&config, &data_dir, &default_dir),
+        read_only: false,
+        forked_from: Some(format!("{}/{}/{}", repository, folder, source.id)),
+        ..source
+    };
+    write_atomic(&file_path, serialize_markdown_prompt(&prompt)).map_err(|e| e.to_string())?;
+
+    record_prompt_revision(&app_handle, &data_dir, &prompt);
+    auto_commit(&app_handle, &data_dir, &format!("Fork prompt: {}", prompt.title));
+    refresh_tray_menu(&app_handle);
+
+    Ok(prompt)
+}
@@ -0,0 +1,188 @@
+use super::file::{check_prompt_ref, ensure_directories, load_config_sync, refresh_prompt_hotkeys, PROMPT_FOLDERS};
+use super::git::auto_commit;
+use super::vault::VAULT_FOLDER;
+use crate::models::{HistoryConfig, PromptItem, PromptRevision};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{
+    append_json_line, diff_lines, generate_id, generate_timestamp, parse_markdown_prompt, prompt_frontmatter,
+    read_json_lines, serialize_markdown_prompt, unix_timestamp, write_json_lines, AppError, DiffSegment,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const HISTORY_DIR: &str = ".history";
+
+fn revisions_path(data_dir: &Path, folder: &str, id: &str) -> PathBuf {
+    data_dir.join(HISTORY_DIR).join(folder).join(format!("{}.jsonl", id))
+}
+
+/// folder 和 id 来自前端并会拼进路径，读取前先校验；保险库没有修订记录
+fn load_revisions(data_dir: &Path, folder: &str, id: &str) -> Result<Vec<PromptRevision>, AppError> {
+    if !PROMPT_FOLDERS.contains(&folder) {
+        return Err(AppError::localized("VALIDATION_ERROR", "unknown_folder", &[&folder]));
+    }
+    check_prompt_ref(folder, id)?;
+    read_json_lines(&revisions_path(data_dir, folder, id))
+}
+
+fn find_revision(revisions: &[PromptRevision], revision_id: &str) -> Result<PromptRevision, AppError> {
+    revisions
+        .iter()
+        .find(|r| r.id == revision_id)
+        .cloned()
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "revision_not_found", &[&revision_id]))
+}
+
+/// 按保留策略裁剪修订；最新的一条修订总是保留
+fn apply_retention(revisions: &mut Vec<PromptRevision>, config: &HistoryConfig) -> bool {
+    let before = revisions.len();
+
+    if config.max_age_days > 0 && revisions.len() > 1 {
+        let cutoff = unix_timestamp().saturating_sub(u64::from(config.max_age_days) * 86400);
+        let latest = revisions.len() - 1;
+        let mut idx = 0;
+        revisions.retain(|r| {
+            let keep = idx == latest || r.timestamp >= cutoff;
+            idx += 1;
+            keep
+        });
+    }
+
+    let max = config.max_revisions as usize;
+    if max > 0 && revisions.len() > max {
+        revisions.drain(..revisions.len() - max);
+    }
+
+    revisions.len() != before
+}
+
+/// 每次保存 prompt 后调用，记录一条修订；保险库中的 prompt 不记录，避免明文落盘
+pub(crate) fn record_revision(data_dir: &Path, prompt: &PromptItem, config: &HistoryConfig) -> Result<(), AppError> {
+    if prompt.folder == VAULT_FOLDER {
+        return Ok(());
+    }
+    let path = revisions_path(data_dir, &prompt.folder, &prompt.id);
+
+    let revision = PromptRevision {
+        id: generate_id(),
+        prompt_id: prompt.id.clone(),
+        folder: prompt.folder.clone(),
+        saved_at: generate_timestamp(),
+        timestamp: unix_timestamp(),
+        frontmatter: prompt_frontmatter(prompt),
+        content: prompt.content.clone(),
+    };
+    append_json_line(&path, &revision)?;
+
+    let mut revisions: Vec<PromptRevision> = read_json_lines(&path)?;
+    if apply_retention(&mut revisions, config) {
+        write_json_lines(&path, &revisions)?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_prompt_revisions(
+    app_handle: AppHandle,
+    id: String,
+    folder: String,
+) -> Result<Vec<PromptRevision>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let mut revisions = load_revisions(&data_dir, &folder, &id).map_err(|e| e.to_string())?;
+    revisions.reverse();
+    Ok(revisions)
+}
+
+#[tauri::command]
+pub async fn diff_prompt_revisions(
+    app_handle: AppHandle,
+    id: String,
+    folder: String,
+    from_revision: String,
+    to_revision: String,
+) -> Result<Vec<DiffSegment>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let revisions = load_revisions(&data_dir, &folder, &id).map_err(|e| e.to_string())?;
+
+    let from = find_revision(&revisions, &from_revision).map_err(|e| e.to_string())?;
+    let to = find_revision(&revisions, &to_revision).map_err(|e| e.to_string())?;
+
+    Ok(diff_lines(&from.content, &to.content))
+}
+
+#[tauri::command]
+pub async fn restore_prompt_revision(
+    app_handle: AppHandle,
+    id: String,
+    folder: String,
+    revision_id: String,
+) -> Result<PromptItem, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let revisions = load_revisions(&data_dir, &folder, &id).map_err(|e| e.to_string())?;
+    let revision = find_revision(&revisions, &revision_id).map_err(|e| e.to_string())?;
+
+    let file_path = data_dir.join(&folder).join(format!("{}.md", id));
+    let path_str = file_path.to_string_lossy().to_string();
+
+    // 文件仍存在时保留其使用统计，只恢复内容相关字段
+    let mut prompt = if file_path.exists() {
+        let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+        parse_markdown_prompt(&content, &path_str, &folder).map_err(|e| e.to_string())?
+    } else {
+        PromptItem {
+            id: id.clone(),
+            title: String::new(),
+            content: String::new(),
+            tags: vec![],
+            description: None,
+            use_count: revision.frontmatter.use_count,
+            last_used: revision.frontmatter.last_used.clone(),
+            created_at: revision.frontmatter.created_at.clone(),
+            updated_at: String::new(),
+            file_path: path_str,
+            folder: folder.clone(),
+            hotkey: None,
+            pinned: revision.frontmatter.pinned,
+            sort_order: revision.frontmatter.sort_order,
+            repository: None,
+            read_only: false,
+            forked_from: revision.frontmatter.forked_from,
+        }
+    };
+
+    prompt.title = revision.frontmatter.title;
+    prompt.tags = revision.frontmatter.tags;
+    prompt.description = revision.frontmatter.description;
+    prompt.content = revision.content;
+    prompt.hotkey = revision.frontmatter.hotkey;
+    prompt.updated_at = generate_timestamp();
+
+    let markdown = serialize_markdown_prompt(&prompt);
+    fs::write(&file_path, markdown)
+        .map_err(|e| AppError::io_failed("write_file_failed", file_path.display(), e).to_string())?;
+
+    let history_config = load_config_sync(&app_handle).map(|c| c.history).unwrap_or_default();
+    record_revision(&data_dir, &prompt, &history_config).map_err(|e| e.to_string())?;
+    auto_commit(&app_handle, &data_dir, &format!("Restore prompt: {}", prompt.title));
+    refresh_prompt_hotkeys(&app_handle);
+    refresh_tray_menu(&app_handle);
+
+    Ok(prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_revision_paths_outside_the_history() {
+        let data_dir = std::env::temp_dir().join(format!("promptflow-revisions-{}", std::process::id()));
+        for (folder, id) in [("../..", "x"), (VAULT_FOLDER, "a"), ("favorites", "../../config"), ("templates", "a/b")] {
+            let err = load_revisions(&data_dir, folder, id).unwrap_err();
+            assert_eq!(err.code, "VALIDATION_ERROR", "{}/{}", folder, id);
+        }
+        assert!(load_revisions(&data_dir, "favorites", "missing").unwrap().is_empty());
+    }
+}
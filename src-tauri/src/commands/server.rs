@@ -0,0 +1,19 @@
+use super::config::save_config;
+use super::file::load_config_sync;
+use crate::services::server::{generate_server_token, server_status, ServerStatus};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn get_server_status(app_handle: AppHandle) -> Result<ServerStatus, String> {
+    Ok(server_status(&app_handle))
+}
+
+/// 生成新令牌并立即生效，旧令牌随之失效
+#[tauri::command]
+pub async fn regenerate_server_token(app_handle: AppHandle) -> Result<String, String> {
+    let mut config = load_config_sync(&app_handle).map_err(|e| e.to_string())?;
+    let token = generate_server_token().map_err(|e| e.to_string())?;
+    config.server.token = token.clone();
+    save_config(app_handle, config).await?;
+    Ok(token)
+}
@@ -0,0 +1,144 @@
+use super::file::{ensure_directories, load_all_prompts, load_config_sync};
+use super::git::auto_commit;
+use super::revision::record_revision;
+use super::vault::write_prompt_file;
+use crate::models::{PromptItem, TagChange, TagChangeReport, TagCount, TagFailure};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{generate_timestamp, serialize_markdown_prompt, AppError};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// 对所有 prompt 的标签应用 `transform`，只重写标签实际发生变化的文件
+fn rewrite_tags<F>(app_handle: &AppHandle, commit_message: &str, transform: F) -> Result<TagChangeReport, AppError>
+where
+    F: Fn(&[String]) -> Vec<String>,
+{
+    let data_dir = ensure_directories(app_handle)?;
+    let history_config = load_config_sync(app_handle).map(|c| c.history).unwrap_or_default();
+    let mut report = TagChangeReport::default();
+
+    for mut prompt in load_all_prompts(&data_dir, true)? {
+        let before = prompt.tags.clone();
+        let transformed = transform(&before);
+        if transformed == before {
+            continue;
+        }
+        let after = dedupe_tags(transformed);
+
+        prompt.tags = after.clone();
+        prompt.updated_at = generate_timestamp();
+
+        match write_prompt(&prompt) {
+            Ok(()) => {
+                if let Err(e) = record_revision(&data_dir, &prompt, &history_config) {
+                    eprintln!("[PromptFlow] Failed to record revision for {}: {}", prompt.id, e);
+                }
+                report.changed.push(TagChange {
+                    id: prompt.id,
+                    folder: prompt.folder,
+                    file_path: prompt.file_path,
+                    before,
+                    after,
+                });
+            }
+            Err(e) => report.failed.push(TagFailure {
+                file_path: prompt.file_path,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    if !report.changed.is_empty() {
+        auto_commit(app_handle, &data_dir, commit_message);
+        refresh_tray_menu(app_handle);
+    }
+
+    Ok(report)
+}
+
+fn write_prompt(prompt: &PromptItem) -> Result<(), AppError> {
+    write_prompt_file(Path::new(&prompt.file_path), &serialize_markdown_prompt(prompt))
+}
+
+fn dedupe_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        if !tag.is_empty() && !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+fn normalize_tag(tag: &str) -> Result<String, AppError> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(AppError::localized("VALIDATION_ERROR", "tag_empty", &[]));
+    }
+    // 标签以 "a", "b" 形式写入 frontmatter，含引号或逗号会破坏解析
+    if tag.contains(['"', ',', '[', ']']) {
+        return Err(AppError::localized("VALIDATION_ERROR", "tag_invalid", &[&tag]));
+    }
+    Ok(tag.to_string())
+}
+
+#[tauri::command]
+pub async fn list_tags(app_handle: AppHandle) -> Result<Vec<TagCount>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let prompts = load_all_prompts(&data_dir, true).map_err(|e| e.to_string())?;
+
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for prompt in &prompts {
+        for tag in dedupe_tags(prompt.tags.clone()) {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+
+    let mut tags: Vec<TagCount> = counts.into_iter().map(|(tag, count)| TagCount { tag, count }).collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    Ok(tags)
+}
+
+#[tauri::command]
+pub async fn rename_tag(app_handle: AppHandle, from: String, to: String) -> Result<TagChangeReport, String> {
+    let from = normalize_tag(&from).map_err(|e| e.to_string())?;
+    let to = normalize_tag(&to).map_err(|e| e.to_string())?;
+
+    let message = format!("Rename tag: {} -> {}", from, to);
+    rewrite_tags(&app_handle, &message, |tags| {
+        tags.iter()
+            .map(|t| if *t == from { to.clone() } else { t.clone() })
+            .collect()
+    })
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn merge_tags(app_handle: AppHandle, sources: Vec<String>, target: String) -> Result<TagChangeReport, String> {
+    let target = normalize_tag(&target).map_err(|e| e.to_string())?;
+    let sources = sources
+        .iter()
+        .map(|s| normalize_tag(s))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let message = format!("Merge tags: {} -> {}", sources.join(", "), target);
+    rewrite_tags(&app_handle, &message, |tags| {
+        tags.iter()
+            .map(|t| if sources.contains(t) { target.clone() } else { t.clone() })
+            .collect()
+    })
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_tag(app_handle: AppHandle, tag: String) -> Result<TagChangeReport, String> {
+    let tag = normalize_tag(&tag).map_err(|e| e.to_string())?;
+
+    let message = format!("Delete tag: {}", tag);
+    rewrite_tags(&app_handle, &message, |tags| {
+        tags.iter().filter(|t| **t != tag).cloned().collect()
+    })
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,186 @@
+use super::file::{ensure_directories, load_config_sync, refresh_prompt_hotkeys};
+use super::git::auto_commit;
+use crate::models::TrashItem;
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{generate_id, generate_timestamp, is_safe_file_name, move_file, unix_timestamp, AppError};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const TRASH_DIR: &str = ".trash";
+const TRASH_FILES_DIR: &str = "files";
+
+fn trash_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(TRASH_DIR)
+}
+
+fn metadata_path(data_dir: &Path, trash_id: &str) -> PathBuf {
+    trash_dir(data_dir).join(format!("{}.json", trash_id))
+}
+
+fn payload_path(data_dir: &Path, trash_id: &str) -> PathBuf {
+    trash_dir(data_dir).join(TRASH_FILES_DIR).join(trash_id)
+}
+
+fn load_trash_items(data_dir: &Path) -> Result<Vec<TrashItem>, AppError> {
+    let dir = trash_dir(data_dir);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut items = vec![];
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e == "json").unwrap_or(false) {
+            if let Ok(content) = fs::read_to_string(&path) {
+                if let Ok(item) = serde_json::from_str::<TrashItem>(&content) {
+                    items.push(item);
+                }
+            }
+        }
+    }
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_timestamp));
+    Ok(items)
+}
+
+// id 会被拼进回收站路径，必须是回收站中已有条目的 id
+fn find_trash_item(data_dir: &Path, trash_id: &str) -> Result<TrashItem, AppError> {
+    let not_found = || AppError::localized("NOT_FOUND", "trash_item_not_found", &[&trash_id]);
+    if !is_safe_file_name(trash_id) {
+        return Err(not_found());
+    }
+    load_trash_items(data_dir)?
+        .into_iter()
+        .find(|item| item.id == trash_id)
+        .ok_or_else(not_found)
+}
+
+fn remove_trash_item(data_dir: &Path, trash_id: &str) -> Result<(), AppError> {
+    let payload = payload_path(data_dir, trash_id);
+    if payload.exists() {
+        fs::remove_file(&payload)?;
+    }
+    let metadata = metadata_path(data_dir, trash_id);
+    if metadata.exists() {
+        fs::remove_file(&metadata)?;
+    }
+    Ok(())
+}
+
+/// 把文件移入回收站并写入元数据；prompt 需要提供所在目录与 id 以便原样恢复
+pub(crate) fn move_to_trash(
+    data_dir: &Path,
+    path: &Path,
+    folder: Option<&str>,
+    prompt_id: Option<&str>,
+    title: &str,
+) -> Result<TrashItem, AppError> {
+    if !path.is_file() {
+        return Err(AppError::localized("NOT_FOUND", "file_not_found", &[&path.display()]));
+    }
+
+    let item = TrashItem {
+        id: generate_id(),
+        original_path: path.to_string_lossy().to_string(),
+        original_folder: folder.map(|f| f.to_string()),
+        prompt_id: prompt_id.map(|id| id.to_string()),
+        title: title.to_string(),
+        deleted_at: generate_timestamp(),
+        deleted_timestamp: unix_timestamp(),
+    };
+
+    move_file(path, &payload_path(data_dir, &item.id))?;
+    let metadata = serde_json::to_string_pretty(&item)?;
+    fs::write(metadata_path(data_dir, &item.id), metadata)?;
+
+    Ok(item)
+}
+
+/// 清除超过保留天数的回收站条目，返回清除数量
+pub(crate) fn purge_expired_trash(data_dir: &Path, retention_days: u32) -> Result<usize, AppError> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = unix_timestamp().saturating_sub(u64::from(retention_days) * 86400);
+    let mut purged = 0;
+    for item in load_trash_items(data_dir)? {
+        if item.deleted_timestamp < cutoff {
+            remove_trash_item(data_dir, &item.id)?;
+            purged += 1;
+        }
+    }
+
+    Ok(purged)
+}
+
+/// 启动时按配置自动清理回收站
+pub fn auto_purge_trash(app_handle: &AppHandle) {
+    let Ok(data_dir) = ensure_directories(app_handle) else {
+        return;
+    };
+    let retention_days = load_config_sync(app_handle)
+        .map(|c| c.trash.retention_days)
+        .unwrap_or_default();
+
+    if let Err(e) = purge_expired_trash(&data_dir, retention_days) {
+        eprintln!("[PromptFlow] Failed to purge trash: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn list_trash(app_handle: AppHandle) -> Result<Vec<TrashItem>, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let retention_days = load_config_sync(&app_handle)
+        .map(|c| c.trash.retention_days)
+        .unwrap_or_default();
+
+    purge_expired_trash(&data_dir, retention_days).map_err(|e| e.to_string())?;
+    load_trash_items(&data_dir).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_prompt(app_handle: AppHandle, trash_id: String) -> Result<TrashItem, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let item = find_trash_item(&data_dir, &trash_id).map_err(|e| e.to_string())?;
+
+    // prompt 恢复到当前数据目录（存储路径可能已变更），其他文件恢复到原路径
+    let target = match (&item.original_folder, &item.prompt_id) {
+        (Some(folder), Some(prompt_id)) => data_dir.join(folder).join(format!("{}.md", prompt_id)),
+        _ => PathBuf::from(&item.original_path),
+    };
+
+    if target.exists() {
+        return Err(AppError::localized("VALIDATION_ERROR", "restore_target_exists", &[&target.display()]).to_string());
+    }
+
+    move_file(&payload_path(&data_dir, &item.id), &target).map_err(|e| e.to_string())?;
+    remove_trash_item(&data_dir, &item.id).map_err(|e| e.to_string())?;
+
+    if item.prompt_id.is_some() {
+        auto_commit(&app_handle, &data_dir, &format!("Restore prompt: {}", item.title));
+        refresh_prompt_hotkeys(&app_handle);
+        refresh_tray_menu(&app_handle);
+    }
+
+    Ok(item)
+}
+
+#[tauri::command]
+pub async fn empty_trash(app_handle: AppHandle, trash_id: Option<String>) -> Result<usize, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+
+    if let Some(trash_id) = trash_id {
+        let item = find_trash_item(&data_dir, &trash_id).map_err(|e| e.to_string())?;
+        remove_trash_item(&data_dir, &item.id).map_err(|e| e.to_string())?;
+        return Ok(1);
+    }
+
+    let items = load_trash_items(&data_dir).map_err(|e| e.to_string())?;
+    for item in &items {
+        remove_trash_item(&data_dir, &item.id).map_err(|e| e.to_string())?;
+    }
+
+    Ok(items.len())
+}
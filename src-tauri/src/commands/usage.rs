@@ -0,0 +1,369 @@
+use super::file::{ensure_directories, load_all_prompts};
+use crate::models::{PromptItem, PromptUsage, TagCount, UsageAction, UsageBucket, UsageEvent, UsageReport};
+use crate::utils::{
+    append_json_line, civil_date, format_timestamp, read_json_lines, unix_timestamp, write_json_lines, AppError,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::AppHandle;
+
+const USAGE_LOG_FILE: &str = ".usage.jsonl";
+const DAY_SECS: u64 = 86400;
+// 超过保留天数的事件按天合并，日志大小只随使用过的天数和 prompt 数增长
+const RAW_EVENT_RETENTION_DAYS: u64 = 90;
+
+struct EventCache {
+    path: PathBuf,
+    len: u64,
+    modified: SystemTime,
+    events: Arc<Vec<UsageEvent>>,
+}
+
+// 列表和托盘每次刷新都要叠加使用统计，日志未变化时复用上次解析的结果
+static EVENT_CACHE: Mutex<Option<EventCache>> = Mutex::new(None);
+
+fn usage_log_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(USAGE_LOG_FILE)
+}
+
+/// 读取事件日志；文件长度和修改时间都没变时直接返回缓存
+fn load_usage_events(data_dir: &Path) -> Result<Arc<Vec<UsageEvent>>, AppError> {
+    let path = usage_log_path(data_dir);
+    let Ok(metadata) = fs::metadata(&path) else {
+        return Ok(Arc::new(vec![]));
+    };
+    let (len, modified) = (metadata.len(), metadata.modified()?);
+
+    let mut cache = EVENT_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cache.as_ref().filter(|c| c.path == path && c.len == len && c.modified == modified) {
+        return Ok(cached.events.clone());
+    }
+
+    let events = Arc::new(read_json_lines(&path)?);
+    *cache = Some(EventCache {
+        path,
+        len,
+        modified,
+        events: events.clone(),
+    });
+    Ok(events)
+}
+
+/// 把早于 `cutoff` 的事件按 prompt、动作、预设和日期合并为一条
+fn compact_events(events: Vec<UsageEvent>, cutoff: u64) -> Vec<UsageEvent> {
+    let (old, recent): (Vec<UsageEvent>, Vec<UsageEvent>) = events.into_iter().partition(|e| e.timestamp < cutoff);
+
+    let mut merged: HashMap<(String, String, UsageAction, Option<String>, u64), UsageEvent> = HashMap::new();
+    for event in old {
+        let key = (
+            event.folder.clone(),
+            event.prompt_id.clone(),
+            event.action,
+            event.preset.clone(),
+            event.timestamp / DAY_SECS,
+        );
+        match merged.get_mut(&key) {
+            Some(entry) => {
+                entry.count = entry.count.saturating_add(event.count);
+                entry.timestamp = entry.timestamp.max(event.timestamp);
+            }
+            None => {
+                merged.insert(key, event);
+            }
+        }
+    }
+
+    let mut compacted: Vec<UsageEvent> = merged.into_values().collect();
+    compacted.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.prompt_id.cmp(&b.prompt_id)));
+    compacted.extend(recent);
+    compacted
+}
+
+/// 合并过期的原始事件，返回减少的行数
+pub(crate) fn compact_usage_log(data_dir: &Path) -> Result<usize, AppError> {
+    let path = usage_log_path(data_dir);
+    let events: Vec<UsageEvent> = read_json_lines(&path)?;
+    let before = events.len();
+
+    let cutoff = unix_timestamp().saturating_sub(RAW_EVENT_RETENTION_DAYS * DAY_SECS);
+    let compacted = compact_events(events, cutoff);
+    if compacted.len() < before {
+        write_json_lines(&path, &compacted)?;
+    }
+    Ok(before - compacted.len())
+}
+
+/// 启动时压缩使用日志
+pub fn auto_compact_usage(app_handle: &AppHandle) {
+    let Ok(data_dir) = ensure_directories(app_handle) else {
+        return;
+    };
+    if let Err(e) = compact_usage_log(&data_dir) {
+        eprintln!("[PromptFlow] Failed to compact usage log: {}", e);
+    }
+}
+
+/// 追加一条使用事件；不修改 prompt 文件，`updated_at` 只随内容编辑变化
+pub(crate) fn record_usage_event(
+    data_dir: &Path,
+    prompt_id: &str,
+    folder: &str,
+    action: UsageAction,
+    preset: Option<String>,
+) -> Result<(), AppError> {
+    let event = UsageEvent {
+        prompt_id: prompt_id.to_string(),
+        folder: folder.to_string(),
+        action,
+        preset,
+        timestamp: unix_timestamp(),
+        count: 1,
+    };
+    append_json_line(&usage_log_path(data_dir), &event)
+}
+
+// 记录失败不应影响复制、渲染本身
+pub(crate) fn log_usage(data_dir: &Path, prompt_id: &str, folder: &str, action: UsageAction) {
+    if let Err(e) = record_usage_event(data_dir, prompt_id, folder, action, None) {
+        eprintln!("[PromptFlow] Failed to record usage for {}: {}", prompt_id, e);
+    }
+}
+
+/// 把事件日志叠加到 frontmatter 中的历史 use_count / last_used 上
+pub(crate) fn apply_usage_stats(data_dir: &Path, prompts: &mut [PromptItem]) {
+    let events = match load_usage_events(data_dir) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[PromptFlow] Failed to read usage log: {}", e);
+            return;
+        }
+    };
+
+    let mut stats: HashMap<(&str, &str), (u32, u64)> = HashMap::new();
+    for event in events.iter() {
+        let entry = stats.entry((&event.folder, &event.prompt_id)).or_default();
+        entry.0 = entry.0.saturating_add(event.count);
+        entry.1 = entry.1.max(event.timestamp);
+    }
+
+    for prompt in prompts.iter_mut() {
+        if let Some(&(count, latest)) = stats.get(&(prompt.folder.as_str(), prompt.id.as_str())) {
+            prompt.use_count = prompt.use_count.saturating_add(count);
+            let latest = format_timestamp(latest);
+            if prompt.last_used.as_deref().map(|l| l < latest.as_str()).unwrap_or(true) {
+                prompt.last_used = Some(latest);
+            }
+        }
+    }
+}
+
+/// 合并 prompt 时把被合并项的使用记录转到保留项上
+pub(crate) fn reassign_usage(data_dir: &Path, from: &[(String, String)], to_folder: &str, to_id: &str) -> Result<(), AppError> {
+    let path = usage_log_path(data_dir);
+    let mut events: Vec<UsageEvent> = read_json_lines(&path)?;
+
+    let mut changed = false;
+    for event in events.iter_mut() {
+        if from.iter().any(|(folder, id)| *folder == event.folder && *id == event.prompt_id) {
+            event.folder = to_folder.to_string();
+            event.prompt_id = to_id.to_string();
+            changed = true;
+        }
+    }
+
+    if changed {
+        write_json_lines(&path, &events)?;
+    }
+    Ok(())
+}
+
+/// 事件所在桶的起始日（自 1970-01-01 起的天数）；按周统计时以周一为起点
+fn bucket_day(timestamp: u64, weekly: bool) -> u64 {
+    let day = timestamp / DAY_SECS;
+    if weekly {
+        // 1970-01-01 是周四
+        day - (day + 3) % 7
+    } else {
+        day
+    }
+}
+
+fn build_report(prompts: &[PromptItem], events: &[UsageEvent], from: u64, to: u64, weekly: bool, limit: usize) -> UsageReport {
+    let events: Vec<&UsageEvent> = events.iter().filter(|e| e.timestamp >= from && e.timestamp <= to).collect();
+    let lookup: HashMap<(&str, &str), &PromptItem> = prompts
+        .iter()
+        .map(|p| ((p.folder.as_str(), p.id.as_str()), p))
+        .collect();
+
+    let mut per_bucket: HashMap<u64, u32> = HashMap::new();
+    let mut per_prompt: HashMap<(&str, &str), u32> = HashMap::new();
+    let mut per_tag: HashMap<&str, u32> = HashMap::new();
+    for event in &events {
+        *per_bucket.entry(bucket_day(event.timestamp, weekly)).or_default() += event.count;
+
+        let key = (event.folder.as_str(), event.prompt_id.as_str());
+        *per_prompt.entry(key).or_default() += event.count;
+        if let Some(prompt) = lookup.get(&key) {
+            for tag in &prompt.tags {
+                *per_tag.entry(tag.as_str()).or_default() += event.count;
+            }
+        }
+    }
+
+    // 补齐没有事件的桶，方便前端直接画图
+    let step = if weekly { 7 } else { 1 };
+    let first = if from > 0 {
+        from
+    } else {
+        events.iter().map(|e| e.timestamp).min().unwrap_or(to)
+    };
+    let mut timeline = vec![];
+    let mut day = bucket_day(first, weekly);
+    while day <= bucket_day(to, weekly) {
+        timeline.push(UsageBucket {
+            start: day * DAY_SECS,
+            date: civil_date(day),
+            count: per_bucket.get(&day).copied().unwrap_or(0),
+        });
+        day += step;
+    }
+
+    // 已删除的 prompt 不出现在排行中
+    let mut top_prompts: Vec<PromptUsage> = per_prompt
+        .iter()
+        .filter_map(|(key, &count)| {
+            lookup.get(key).map(|p| PromptUsage {
+                id: p.id.clone(),
+                folder: p.folder.clone(),
+                title: p.title.clone(),
+                count,
+            })
+        })
+        .collect();
+    top_prompts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.title.cmp(&b.title)));
+    top_prompts.truncate(limit);
+
+    let mut unused_prompts: Vec<PromptUsage> = prompts
+        .iter()
+        .filter(|p| !per_prompt.contains_key(&(p.folder.as_str(), p.id.as_str())))
+        .map(|p| PromptUsage {
+            id: p.id.clone(),
+            folder: p.folder.clone(),
+            title: p.title.clone(),
+            count: 0,
+        })
+        .collect();
+    unused_prompts.sort_by(|a, b| a.title.cmp(&b.title));
+
+    let mut top_tags: Vec<TagCount> = per_tag
+        .into_iter()
+        .map(|(tag, count)| TagCount {
+            tag: tag.to_string(),
+            count,
+        })
+        .collect();
+    top_tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    top_tags.truncate(limit);
+
+    UsageReport {
+        from,
+        to,
+        total: events.iter().map(|e| e.count).sum(),
+        timeline,
+        top_prompts,
+        unused_prompts,
+        top_tags,
+    }
+}
+
+#[tauri::command]
+pub async fn record_usage(
+    app_handle: AppHandle,
+    id: String,
+    folder: String,
+    action: UsageAction,
+    preset: Option<String>,
+) -> Result<(), String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    record_usage_event(&data_dir, &id, &folder, action, preset).map_err(|e| e.to_string())
+}
+
+/// `days` 为统计最近多少天（默认 30，0 表示全部）；`bucket` 为 "day" 或 "week"
+#[tauri::command]
+pub async fn get_usage_report(
+    app_handle: AppHandle,
+    days: Option<u32>,
+    bucket: Option<String>,
+    limit: Option<usize>,
+) -> Result<UsageReport, String> {
+    let weekly = match bucket.as_deref().unwrap_or("day") {
+        "day" => false,
+        "week" => true,
+        other => return Err(AppError::localized("VALIDATION_ERROR", "unknown_bucket", &[&other]).to_string()),
+    };
+
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    let prompts = load_all_prompts(&data_dir, true).map_err(|e| e.to_string())?;
+    let events = load_usage_events(&data_dir).map_err(|e| e.to_string())?;
+
+    let to = unix_timestamp();
+    let from = match days.unwrap_or(30) {
+        0 => 0,
+        days => to.saturating_sub(u64::from(days) * DAY_SECS),
+    };
+
+    Ok(build_report(&prompts, &events, from, to, weekly, limit.unwrap_or(10)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str, action: UsageAction, timestamp: u64) -> UsageEvent {
+        UsageEvent {
+            prompt_id: id.to_string(),
+            folder: "favorites".to_string(),
+            action,
+            preset: None,
+            timestamp,
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn compacts_old_events_per_day_and_keeps_recent_ones() {
+        let cutoff = 100 * DAY_SECS;
+        let events = vec![
+            event("a", UsageAction::Copy, 3 * DAY_SECS + 10),
+            event("a", UsageAction::Copy, 3 * DAY_SECS + 500),
+            event("a", UsageAction::Render, 3 * DAY_SECS + 20),
+            event("a", UsageAction::Copy, 4 * DAY_SECS),
+            event("b", UsageAction::Copy, 3 * DAY_SECS + 30),
+            event("a", UsageAction::Copy, cutoff),
+            event("a", UsageAction::Copy, cutoff + 1),
+        ];
+
+        let compacted = compact_events(events.clone(), cutoff);
+        assert_eq!(compacted.len(), 6);
+        let merged = compacted.iter().find(|e| e.count == 2).unwrap();
+        assert_eq!((merged.prompt_id.as_str(), merged.timestamp), ("a", 3 * DAY_SECS + 500));
+        assert_eq!(compacted.iter().filter(|e| e.timestamp >= cutoff).count(), 2);
+
+        // 再次压缩结果不变，统计总数也不变
+        let again = compact_events(compacted.clone(), cutoff);
+        assert_eq!(again.len(), compacted.len());
+        let report = build_report(&[], &again, 0, cutoff + 1, false, 10);
+        assert_eq!(report.total, events.len() as u32);
+    }
+
+    #[test]
+    fn reads_legacy_events_without_count() {
+        let event: UsageEvent =
+            serde_json::from_str(r#"{"promptId":"a","folder":"favorites","action":"copy","timestamp":5}"#).unwrap();
+        assert_eq!(event.count, 1);
+        assert!(!serde_json::to_string(&event).unwrap().contains("count"));
+    }
+}
@@ -0,0 +1,299 @@
+use super::file::{ensure_directories, refresh_prompt_hotkeys};
+use crate::models::{PromptItem, VaultMeta, VaultStatus};
+use crate::services::tray::refresh_tray_menu;
+use crate::utils::{
+    decrypt, derive_key, encrypt, from_hex, generate_timestamp, is_encrypted, random_bytes, to_hex, write_atomic,
+    AppError, KEY_LEN, SALT_LEN,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use zeroize::Zeroizing;
+
+/// 保险库目录，与 favorites、templates 并列；其中的 .md 文件都是密文
+pub(crate) const VAULT_FOLDER: &str = "vault";
+const VAULT_META_FILE: &str = ".vault.json";
+const VAULT_VERSION: u32 = 1;
+const CHECK_PLAINTEXT: &[u8] = b"PromptFlow vault";
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+// Argon2id 参数，与 argon2 crate 的默认值一致
+const KDF_MEMORY_KIB: u32 = 19 * 1024;
+const KDF_ITERATIONS: u32 = 2;
+const KDF_PARALLELISM: u32 = 1;
+
+struct VaultSession {
+    data_dir: PathBuf,
+    key: Zeroizing<[u8; KEY_LEN]>,
+}
+
+// 解锁后的密钥只保存在内存中，退出应用或锁定后即失效
+static SESSION: Mutex<Option<VaultSession>> = Mutex::new(None);
+
+// 会话是全局的，测试之间需要串行使用保险库
+#[cfg(test)]
+pub(crate) static TEST_SESSION_LOCK: Mutex<()> = Mutex::new(());
+
+fn meta_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(VAULT_FOLDER).join(VAULT_META_FILE)
+}
+
+fn load_meta(data_dir: &Path) -> Result<Option<VaultMeta>, AppError> {
+    let path = meta_path(data_dir);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(&path)?)?))
+}
+
+/// 用当前会话的密钥执行 `f`；密钥不出锁，也不会被复制。未解锁时返回 VAULT_LOCKED
+fn with_session_key<T>(
+    data_dir: &Path,
+    f: impl FnOnce(&[u8; KEY_LEN]) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    match session.as_ref().filter(|s| s.data_dir == data_dir) {
+        Some(session) => f(&session.key),
+        None => Err(locked_error()),
+    }
+}
+
+// 被替换或清除的旧会话在 drop 时清零密钥
+fn set_session(session: Option<VaultSession>) {
+    *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = session;
+}
+
+pub(crate) fn is_vault_unlocked(data_dir: &Path) -> bool {
+    with_session_key(data_dir, |_| Ok(())).is_ok()
+}
+
+/// 文件位于 `<data_dir>/vault/` 下时返回 data_dir
+fn vault_data_dir(path: &Path) -> Option<&Path> {
+    let parent = path.parent()?;
+    if parent.file_name()? == VAULT_FOLDER {
+        parent.parent()
+    } else {
+        None
+    }
+}
+
+fn locked_error() -> AppError {
+    AppError::localized("VAULT_LOCKED", "vault_locked", &[])
+}
+
+/// 读取 prompt 文件；保险库中的密文用当前会话的密钥解密，未解锁时返回 VAULT_LOCKED
+pub(crate) fn read_prompt_file(path: &Path) -> Result<String, AppError> {
+    let data = fs::read(path)?;
+    let data = if is_encrypted(&data) {
+        let data_dir = vault_data_dir(path).ok_or_else(locked_error)?;
+        with_session_key(data_dir, |key| decrypt(key, &data))?
+    } else {
+        data
+    };
+    String::from_utf8(data).map_err(|e| AppError::localized("PARSE_ERROR", "invalid_utf8", &[&path.display(), &e]))
+}
+
+/// 写入 prompt 文件；保险库中的文件总是加密后再落盘
+pub(crate) fn write_prompt_file(path: &Path, content: &str) -> Result<(), AppError> {
+    match vault_data_dir(path) {
+        Some(data_dir) => {
+            let encrypted = with_session_key(data_dir, |key| encrypt(key, content.as_bytes()))?;
+            write_atomic(path, encrypted)
+        }
+        None => write_atomic(path, content),
+    }
+}
+
+/// 提交信息会留在 git 历史中，保险库中的 prompt 只写 id
+pub(crate) fn commit_title(prompt: &PromptItem) -> &str {
+    if prompt.folder == VAULT_FOLDER {
+        &prompt.id
+    } else {
+        &prompt.title
+    }
+}
+
+fn unlock_key(meta: &VaultMeta, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LEN]>, AppError> {
+    let invalid = || AppError::localized("PARSE_ERROR", "vault_meta_invalid", &[&VAULT_META_FILE]);
+    let salt = from_hex(&meta.salt).ok_or_else(invalid)?;
+    let check = from_hex(&meta.check).ok_or_else(invalid)?;
+
+    let key = derive_key(passphrase, &salt, meta.memory_kib, meta.iterations, meta.parallelism)?;
+    match decrypt(&key, &check) {
+        Ok(plaintext) if plaintext == CHECK_PLAINTEXT => Ok(key),
+        _ => Err(AppError::localized("PERMISSION_DENIED", "vault_wrong_passphrase", &[])),
+    }
+}
+
+fn vault_status(data_dir: &Path) -> Result<VaultStatus, AppError> {
+    let dir = data_dir.join(VAULT_FOLDER);
+    let item_count = if dir.is_dir() {
+        fs::read_dir(&dir)?
+            .flatten()
+            .filter(|e| e.path().extension().map(|ext| ext == "md").unwrap_or(false))
+            .count()
+    } else {
+        0
+    };
+
+    Ok(VaultStatus {
+        initialized: meta_path(data_dir).is_file(),
+        unlocked: is_vault_unlocked(data_dir),
+        item_count,
+    })
+}
+
+// 解锁状态变化后，保险库中的 prompt 会出现在或消失于快捷键与托盘菜单
+fn vault_changed(app_handle: &AppHandle, data_dir: &Path) -> Result<VaultStatus, AppError> {
+    refresh_prompt_hotkeys(app_handle);
+    refresh_tray_menu(app_handle);
+
+    let status = vault_status(data_dir)?;
+    let _ = app_handle.emit("vault-changed", &status);
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_vault_status(app_handle: AppHandle) -> Result<VaultStatus, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    vault_status(&data_dir).map_err(|e| e.to_string())
+}
+
+/// 在 `data_dir` 创建保险库并立即解锁
+pub(crate) fn create_vault_at(data_dir: &Path, passphrase: &str) -> Result<(), AppError> {
+    if meta_path(data_dir).is_file() {
+        return Err(AppError::localized("VALIDATION_ERROR", "vault_exists", &[]));
+    }
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(AppError::localized("VALIDATION_ERROR", "vault_passphrase_short", &[&MIN_PASSPHRASE_CHARS]));
+    }
+
+    let salt = random_bytes::<SALT_LEN>()?;
+    let key = derive_key(passphrase, &salt, KDF_MEMORY_KIB, KDF_ITERATIONS, KDF_PARALLELISM)?;
+    let meta = VaultMeta {
+        version: VAULT_VERSION,
+        salt: to_hex(&salt),
+        memory_kib: KDF_MEMORY_KIB,
+        iterations: KDF_ITERATIONS,
+        parallelism: KDF_PARALLELISM,
+        check: to_hex(&encrypt(&key, CHECK_PLAINTEXT)?),
+        created_at: generate_timestamp(),
+    };
+    write_atomic(&meta_path(data_dir), serde_json::to_string_pretty(&meta)?)?;
+
+    set_session(Some(VaultSession {
+        data_dir: data_dir.to_path_buf(),
+        key,
+    }));
+    Ok(())
+}
+
+fn unlock_vault_at(data_dir: &Path, passphrase: &str) -> Result<(), AppError> {
+    let meta = load_meta(data_dir)?.ok_or_else(|| AppError::localized("NOT_FOUND", "vault_not_initialized", &[]))?;
+    let key = unlock_key(&meta, passphrase)?;
+
+    set_session(Some(VaultSession {
+        data_dir: data_dir.to_path_buf(),
+        key,
+    }));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_vault(app_handle: AppHandle, passphrase: String) -> Result<VaultStatus, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    create_vault_at(&data_dir, &passphrase).map_err(|e| e.to_string())?;
+    vault_changed(&app_handle, &data_dir).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn unlock_vault(app_handle: AppHandle, passphrase: String) -> Result<VaultStatus, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    unlock_vault_at(&data_dir, &passphrase).map_err(|e| e.to_string())?;
+    vault_changed(&app_handle, &data_dir).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn lock_vault(app_handle: AppHandle) -> Result<VaultStatus, String> {
+    let data_dir = ensure_directories(&app_handle).map_err(|e| e.to_string())?;
+    set_session(None);
+    vault_changed(&app_handle, &data_dir).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::file::load_all_prompts;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("promptflow-vault-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn round_trips_prompts_through_the_vault() {
+        let _guard = TEST_SESSION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let data_dir = temp_dir("round-trip");
+        let path = data_dir.join(VAULT_FOLDER).join("secret.md");
+
+        let err = create_vault_at(&data_dir, "short").unwrap_err();
+        assert_eq!(err.key, Some("vault_passphrase_short"));
+        create_vault_at(&data_dir, PASSPHRASE).unwrap();
+        assert_eq!(create_vault_at(&data_dir, PASSPHRASE).unwrap_err().key, Some("vault_exists"));
+
+        write_prompt_file(&path, "Secret prompt").unwrap();
+        assert!(is_encrypted(&fs::read(&path).unwrap()));
+        assert_eq!(read_prompt_file(&path).unwrap(), "Secret prompt");
+        assert_eq!(vault_status(&data_dir).unwrap().item_count, 1);
+
+        // 普通目录中的文件不加密
+        let plain = data_dir.join("favorites").join("open.md");
+        write_prompt_file(&plain, "Open prompt").unwrap();
+        assert_eq!(fs::read_to_string(&plain).unwrap(), "Open prompt");
+
+        set_session(None);
+        assert_eq!(read_prompt_file(&path).unwrap_err().code, "VAULT_LOCKED");
+        assert_eq!(write_prompt_file(&path, "Overwrite").unwrap_err().code, "VAULT_LOCKED");
+
+        let err = unlock_vault_at(&data_dir, "wrong passphrase").unwrap_err();
+        assert_eq!(err.key, Some("vault_wrong_passphrase"));
+        unlock_vault_at(&data_dir, PASSPHRASE).unwrap();
+        assert_eq!(read_prompt_file(&path).unwrap(), "Secret prompt");
+
+        set_session(None);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn includes_vault_prompts_only_on_request() {
+        let _guard = TEST_SESSION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let data_dir = temp_dir("include");
+        create_vault_at(&data_dir, PASSPHRASE).unwrap();
+        write_prompt_file(&data_dir.join(VAULT_FOLDER).join("secret.md"), "Secret prompt").unwrap();
+
+        let folders = |include_vault| -> Vec<String> {
+            load_all_prompts(&data_dir, include_vault).unwrap().into_iter().map(|p| p.folder).collect()
+        };
+        assert_eq!(folders(true), vec![VAULT_FOLDER]);
+        assert!(folders(false).is_empty());
+
+        set_session(None);
+        assert!(folders(true).is_empty());
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn unlock_requires_an_existing_vault() {
+        let data_dir = temp_dir("missing");
+        let err = unlock_vault_at(&data_dir, PASSPHRASE).unwrap_err();
+        assert_eq!(err.key, Some("vault_not_initialized"));
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+}
@@ -1,34 +1,21 @@
-use tauri::{AppHandle, Manager, WebviewWindow};
+use crate::services::window::{hide_main_window, show_main_window, toggle_main_window};
+use tauri::{AppHandle, WebviewWindow};
 
 #[tauri::command]
 pub async fn toggle_window(app_handle: AppHandle) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        if window.is_visible().unwrap_or(false) {
-            window.hide().map_err(|e| e.to_string())?;
-        } else {
-            window.show().map_err(|e| e.to_string())?;
-            window.set_always_on_top(true).map_err(|e| e.to_string())?;
-            window.set_focus().map_err(|e| e.to_string())?;
-        }
-    }
+    toggle_main_window(&app_handle);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn show_window(app_handle: AppHandle) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        window.show().map_err(|e| e.to_string())?;
-        window.set_always_on_top(true).map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
-    }
+    show_main_window(&app_handle);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn hide_window(app_handle: AppHandle) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        window.hide().map_err(|e| e.to_string())?;
-    }
+    hide_main_window(&app_handle);
     Ok(())
 }
 
@@ -1,3 +1,4 @@
+pub mod cli;
 mod commands;
 mod models;
 mod services;
@@ -6,27 +7,60 @@ mod utils;
 use commands::{
     // File commands
     get_data_directory, read_file, write_file, list_files, delete_file, file_exists,
-    get_all_prompts, get_favorites, get_templates, create_prompt, update_prompt, 
+    get_all_prompts, get_favorites, get_templates, create_prompt, update_prompt, query_prompts,
     delete_prompt, increment_use_count, export_data_dir, init_repository,
+    // Order commands
+    set_prompt_pinned, reorder_prompts,
     // Config commands
     load_config, save_config, reset_config, get_api_key, set_api_key,
     // Window commands
     toggle_window, show_window, hide_window, set_window_position, get_window_position,
     set_window_size, center_window, set_always_on_top, minimize_window, close_window,
+    // Polish history commands
+    record_polish_run, list_polish_history, get_polish_run, delete_polish_history, diff_polish_run,
+    // Revision commands
+    list_prompt_revisions, diff_prompt_revisions, restore_prompt_revision,
+    // Git commands
+    git_init, git_status, git_commit, git_pull, git_push, git_abort_merge,
+    // Trash commands
+    list_trash, restore_prompt, empty_trash,
+    // Tag commands
+    list_tags, rename_tag, merge_tags, delete_tag,
+    // Clipboard commands
+    copy_to_clipboard, paste_to_previous_app, copy_prompt,
+    // Server commands
+    get_server_status, regenerate_server_token,
+    // Duplicate commands
+    find_duplicate_prompts, merge_duplicate_prompts,
+    // Lint commands
+    lint_library,
+    // Usage commands
+    record_usage, get_usage_report,
+    // Template pack commands
+    list_template_packs, install_template_pack, update_template_pack, uninstall_template_pack,
+    // Repository commands
+    list_repositories, add_repository, remove_repository, switch_repository, fork_prompt,
+    // Vault commands
+    get_vault_status, create_vault, unlock_vault, lock_vault,
 };
 
-use tauri::menu::{Menu, MenuItem};
-use tauri::tray::{MouseButton, TrayIconBuilder, TrayIconEvent};
-use tauri::Manager;
+use tauri::{Manager, WindowEvent};
+use tauri_plugin_deep_link::DeepLinkExt;
 
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let mut builder = tauri::Builder::default()
+        // single-instance 必须最先注册，第二个进程会在其他插件初始化之前退出
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            services::instance::on_second_instance(app, args, cwd);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build());
     
     #[cfg(target_os = "macos")]
@@ -40,61 +74,62 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             services::init_panel(&app_handle);
             
-            if let Err(e) = services::setup_global_shortcut(&app_handle) {
+            let config = commands::file::load_config_sync(&app_handle).unwrap_or_default();
+            utils::set_language(utils::Language::from_tag(&config.ui.language));
+            if let Err(e) = services::setup_global_shortcut(&app_handle, &config.ui.hotkey) {
                 eprintln!("Failed to setup global shortcut: {}", e);
             }
+            commands::file::refresh_prompt_hotkeys(&app_handle);
 
             services::accessibility::ensure_accessibility_prompted_once(&app_handle);
 
-            // Setup Tray Icon
-            let quit_i = MenuItem::with_id(app, "quit", "Quit PromptFlow", true, None::<&str>)?;
-            let show_i = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
+            commands::trash::auto_purge_trash(&app_handle);
+            commands::usage::auto_compact_usage(&app_handle);
 
-            // 使用应用默认图标作为 tray icon
-            let icon = app.default_window_icon().cloned().unwrap();
+            services::appearance::apply_appearance(&app_handle, &config.ui);
 
-            let _tray = TrayIconBuilder::new()
-                .icon(icon)
-                .menu(&menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| {
-                    match event.id().as_ref() {
-                        "quit" => {
-                            app.exit(0);
-                        }
-                        "show" => {
-                            if let Some(window) = app.get_webview_window("main") {
-                                let _ = window.show();
-                                let _ = window.set_always_on_top(true);
-                                let _ = window.set_focus();
-                            }
-                        }
-                        _ => {}
-                    }
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            if window.is_visible().unwrap_or(false) {
-                                let _ = window.hide();
-                            } else {
-                                let _ = window.show();
-                                let _ = window.set_always_on_top(true);
-                                let _ = window.set_focus();
-                            }
-                        }
-                    }
-                })
-                .build(app)?;
+            app.manage(services::server::ServerState::default());
+            if let Err(e) = services::server::apply_server_config(&app_handle, &config.server) {
+                eprintln!("[PromptFlow] Failed to start local server: {}", e);
+            }
+
+            // 启动时按 window_position 摆放主窗口
+            services::window::show_main_window(&app_handle);
+
+            services::tray::setup_tray(&app_handle)?;
+
+            // 开发模式和 Linux AppImage 下需要在运行时注册 promptflow:// 协议
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                eprintln!("[PromptFlow] Failed to register deep link scheme: {}", e);
+            }
+            let deep_link_handle = app_handle.clone();
+            app.deep_link().on_open_url(move |event| {
+                services::deep_link::handle_deep_links(&deep_link_handle, event.urls());
+            });
+            // 通过链接冷启动时，链接不会触发 on_open_url
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                services::deep_link::handle_deep_links(&app_handle, urls);
+            }
             
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if window.label() != "main" {
+                return;
+            }
+            match event {
+                WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+                    services::window::schedule_save_window_geometry(window.app_handle());
+                }
+                WindowEvent::ThemeChanged(_) => {
+                    if let Ok(config) = commands::file::load_config_sync(window.app_handle()) {
+                        services::appearance::on_system_theme_changed(window.app_handle(), &config.ui);
+                    }
+                }
+                _ => {}
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // File commands
             get_data_directory,
@@ -104,6 +139,7 @@ pub fn run() {
             delete_file,
             file_exists,
             get_all_prompts,
+            query_prompts,
             get_favorites,
             get_templates,
             create_prompt,
@@ -112,6 +148,9 @@ pub fn run() {
             increment_use_count,
             export_data_dir,
             init_repository,
+            // Order commands
+            set_prompt_pinned,
+            reorder_prompts,
             // Config commands
             load_config,
             save_config,
@@ -129,6 +168,63 @@ pub fn run() {
             set_always_on_top,
             minimize_window,
             close_window,
+            // Polish history commands
+            record_polish_run,
+            list_polish_history,
+            get_polish_run,
+            delete_polish_history,
+            diff_polish_run,
+            // Revision commands
+            list_prompt_revisions,
+            diff_prompt_revisions,
+            restore_prompt_revision,
+            // Git commands
+            git_init,
+            git_status,
+            git_commit,
+            git_pull,
+            git_push,
+            git_abort_merge,
+            // Trash commands
+            list_trash,
+            restore_prompt,
+            empty_trash,
+            // Tag commands
+            list_tags,
+            rename_tag,
+            merge_tags,
+            delete_tag,
+            // Clipboard commands
+            copy_to_clipboard,
+            paste_to_previous_app,
+            copy_prompt,
+            // Server commands
+            get_server_status,
+            regenerate_server_token,
+            // Duplicate commands
+            find_duplicate_prompts,
+            merge_duplicate_prompts,
+            // Lint commands
+            lint_library,
+            // Usage commands
+            record_usage,
+            get_usage_report,
+            // Template pack commands
+            list_template_packs,
+            install_template_pack,
+            update_template_pack,
+            uninstall_template_pack,
+            // Repository commands
+            list_repositories,
+            add_repository,
+            remove_repository,
+            switch_repository,
+            fork_prompt,
+            // Vault commands
+            get_vault_status,
+            create_vault,
+            unlock_vault,
+            lock_vault,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
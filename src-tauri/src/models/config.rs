@@ -1,3 +1,4 @@
+use crate::utils::AppError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +10,17 @@ pub struct AppConfig {
     pub storage: StorageConfig,
     #[serde(default)]
     pub onboarding_completed: bool,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub git: GitConfig,
+    #[serde(default)]
+    pub trash: TrashConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    /// 已登记的 prompt 仓库；当前使用的仓库仍由 storage.path 决定
+    #[serde(default)]
+    pub repositories: Vec<RepositoryConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +78,90 @@ pub struct StorageConfig {
     pub format: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryConfig {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// 只读仓库不能切换为当前仓库，作为共享层叠加在当前仓库之上，其中的 prompt 只能复制后编辑
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryConfig {
+    /// 每个 prompt 最多保留的修订数，0 表示不限制
+    pub max_revisions: u32,
+    /// 修订最长保留天数，0 表示不限制
+    pub max_age_days: u32,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_revisions: 50,
+            max_age_days: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GitConfig {
+    pub enabled: bool,
+    /// 创建/更新/删除 prompt 后自动提交
+    pub auto_commit: bool,
+    /// 远程仓库地址，可以是本地裸仓库路径
+    pub remote_url: String,
+    pub branch: String,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            auto_commit: true,
+            remote_url: String::new(),
+            branch: "main".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrashConfig {
+    /// 回收站条目保留天数，超过后自动清除；0 表示永不自动清除
+    pub retention_days: u32,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerConfig {
+    /// 是否启用本地 HTTP/MCP 服务，只监听 127.0.0.1
+    pub enabled: bool,
+    pub port: u16,
+    /// 请求需携带 `Authorization: Bearer <token>`；启用时为空会自动生成
+    pub token: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 17321,
+            token: String::new(),
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -96,6 +192,43 @@ impl Default for AppConfig {
                 format: "markdown".to_string(),
             },
             onboarding_completed: false,
+            history: HistoryConfig::default(),
+            git: GitConfig::default(),
+            trash: TrashConfig::default(),
+            server: ServerConfig::default(),
+            repositories: vec![],
         }
     }
 }
+
+/// save_config 的结果：配置总会写入，未能生效的设置（快捷键、本地服务）作为警告返回
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSaveReport {
+    pub warnings: Vec<AppError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_missing_fields_in_partial_sections() {
+        // 旧版本或手动编辑的配置只包含部分字段
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value["git"] = serde_json::json!({ "enabled": true });
+        value["history"] = serde_json::json!({ "maxRevisions": 5 });
+        value["trash"] = serde_json::json!({});
+        value["server"] = serde_json::json!({ "port": 9000 });
+        let config: AppConfig = serde_json::from_value(value).unwrap();
+
+        assert!(config.git.enabled);
+        assert!(config.git.auto_commit);
+        assert_eq!(config.git.branch, "main");
+        assert_eq!(config.history.max_revisions, 5);
+        assert_eq!(config.history.max_age_days, 0);
+        assert_eq!(config.trash.retention_days, 30);
+        assert_eq!(config.server.port, 9000);
+        assert!(!config.server.enabled);
+    }
+}
@@ -0,0 +1,27 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 解析后的 promptflow:// 链接
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum DeepLinkAction {
+    /// promptflow://open/<id>[?folder=..]
+    #[serde(rename_all = "camelCase")]
+    Open { id: String, folder: Option<String> },
+    /// promptflow://new?title=..&content=..[&tags=a,b&description=..&folder=..]
+    #[serde(rename_all = "camelCase")]
+    New {
+        title: String,
+        content: String,
+        tags: Vec<String>,
+        description: Option<String>,
+        folder: String,
+    },
+    /// promptflow://render/<id>?变量名=值
+    #[serde(rename_all = "camelCase")]
+    Render {
+        id: String,
+        folder: Option<String>,
+        variables: HashMap<String, String>,
+    },
+}
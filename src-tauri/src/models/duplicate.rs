@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateKind {
+    /// 归一化后内容完全相同
+    Exact,
+    /// MinHash 估计的相似度达到阈值
+    Near,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMember {
+    pub id: String,
+    pub folder: String,
+    pub title: String,
+    pub file_path: String,
+    pub use_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCluster {
+    pub kind: DuplicateKind,
+    /// 簇内任意两条之间的最低相似度（0~1）
+    pub similarity: f64,
+    /// 按使用次数降序，第一条可作为合并时保留的候选
+    pub members: Vec<DuplicateMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptRef {
+    pub id: String,
+    pub folder: String,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// 文件无法被正确加载，会从界面中消失或内容错乱
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintIssue {
+    pub file_path: String,
+    /// 从 1 开始的行号；针对整个文件的问题为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub code: String,
+    pub severity: LintSeverity,
+    pub message: String,
+    pub suggestion: String,
+    /// 可以安全地自动修复（不会丢失文件中的其他内容）
+    pub fixable: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub files_scanned: usize,
+    pub issues: Vec<LintIssue>,
+    /// 本次自动修复的问题
+    pub fixed: Vec<LintIssue>,
+}
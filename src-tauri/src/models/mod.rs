@@ -1,5 +1,33 @@
 pub mod prompt;
 pub mod config;
+pub mod polish;
+pub mod revision;
+pub mod git;
+pub mod trash;
+pub mod tag;
+pub mod window;
+pub mod deep_link;
+pub mod duplicate;
+pub mod lint;
+pub mod usage;
+pub mod query;
+pub mod pack;
+pub mod repository;
+pub mod vault;
 
 pub use prompt::*;
 pub use config::*;
+pub use polish::*;
+pub use revision::*;
+pub use git::*;
+pub use trash::*;
+pub use tag::*;
+pub use window::*;
+pub use deep_link::*;
+pub use duplicate::*;
+pub use lint::*;
+pub use usage::*;
+pub use query::*;
+pub use pack::*;
+pub use repository::*;
+pub use vault::*;
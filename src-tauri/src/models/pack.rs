@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 模板包的 manifest.json；每个条目对应包目录下的 `<id>.md`，
+/// 提供多语言版本时为 `<language>/<id>.md`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackManifest {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 递增的整数版本，大于已安装版本即视为有更新
+    pub version: u32,
+    /// 如 "zh-CN"、"en"；为空表示只有一个版本
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackSource {
+    /// 随应用内置
+    Builtin,
+    /// 放在数据目录 `.packs/` 下
+    Local,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePackInfo {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub version: u32,
+    pub source: PackSource,
+    pub item_count: usize,
+    pub languages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_version: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_language: Option<String>,
+    pub update_available: bool,
+}
+
+/// 已安装的包；`items` 记录每个条目安装时的内容指纹，用于判断用户是否改动过
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledPack {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub items: BTreeMap<String, String>,
+}
+
+/// 数据目录下 `.packs.json` 的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackState {
+    #[serde(default)]
+    pub packs: BTreeMap<String, InstalledPack>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackChangeReport {
+    pub pack_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    /// 新写入的条目
+    pub added: Vec<String>,
+    /// 未被改动、已覆盖为新版本的条目
+    pub updated: Vec<String>,
+    /// 移入回收站的条目
+    pub removed: Vec<String>,
+    /// 用户改动过或与已有 prompt 重名，保持原样的条目
+    pub kept: Vec<String>,
+    /// 用户删除过，不再恢复的条目
+    pub skipped: Vec<String>,
+}
@@ -16,6 +16,24 @@ pub struct PromptItem {
     pub updated_at: String,
     pub file_path: String,
     pub folder: String,
+    /// 该 prompt 专属的全局快捷键，按下后直接复制渲染后的内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
+    /// 置顶的 prompt 在所在目录中排在最前
+    #[serde(default)]
+    pub pinned: bool,
+    /// 手动排序位置，越小越靠前；未设置的排在已排序项之后
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<u32>,
+    /// 所属仓库的 id，只在列表结果中填写，不写入文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    /// 来自只读共享仓库，不能修改或删除
+    #[serde(default)]
+    pub read_only: bool,
+    /// 从共享仓库复制而来时指向原 prompt，格式为 `仓库 id/folder/id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +50,14 @@ pub struct PromptFrontmatter {
     pub last_used: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +69,24 @@ pub struct FileInfo {
     pub modified_at: String,
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub imported: Vec<PromptItem>,
+    /// 已存在或无法解析而跳过的文件（folder/文件名）
+    pub skipped: Vec<String>,
+}
+
+/// 渲染结果；`missing_variables` 为未提供值、保留原样的占位符
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedPrompt {
+    pub id: String,
+    pub folder: String,
+    pub content: String,
+    pub missing_variables: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreatePromptInput {
@@ -52,6 +96,8 @@ pub struct CreatePromptInput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub folder: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,4 +111,7 @@ pub struct UpdatePromptInput {
     pub tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// 空字符串表示清除快捷键
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
 }
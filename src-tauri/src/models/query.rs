@@ -0,0 +1,108 @@
+use super::PromptItem;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// 包含任意一个标签
+    #[default]
+    Any,
+    /// 包含全部标签
+    All,
+}
+
+/// 时间范围按 ISO 8601 字符串比较，`after` / `before` 均为开区间
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFilter {
+    /// 为空表示所有目录
+    #[serde(default)]
+    pub folders: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    /// 按空白分词，每个词都需要出现在标题、描述、标签或正文中
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<DateRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_use_count: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_variables: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
+    /// 同时搜索所有已登记的仓库，而不只是当前仓库
+    #[serde(default)]
+    pub all_repositories: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortField {
+    Title,
+    Created,
+    #[default]
+    Updated,
+    LastUsed,
+    UseCount,
+    /// 需要同时提供 `filter.search`
+    Relevance,
+    /// 置顶优先，其次按手动设置的 sort_order
+    Manual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSort {
+    #[serde(default)]
+    pub field: SortField,
+    /// 默认标题升序，其余字段降序
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<SortDirection>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptQuery {
+    #[serde(default)]
+    pub filter: PromptFilter,
+    #[serde(default)]
+    pub sort: PromptSort,
+    /// 上一页返回的 `next_cursor`，原样传回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPage {
+    pub items: Vec<PromptItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// 满足过滤条件的总数（不受分页影响）
+    pub total: usize,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryInfo {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub read_only: bool,
+    /// 是否为当前使用的仓库
+    pub active: bool,
+    /// 目录是否存在；网络共享等离线时为 false
+    pub available: bool,
+}
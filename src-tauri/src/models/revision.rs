@@ -0,0 +1,15 @@
+use crate::models::PromptFrontmatter;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptRevision {
+    pub id: String,
+    pub prompt_id: String,
+    pub folder: String,
+    pub saved_at: String,
+    /// Unix 秒，用于按保留天数清理
+    pub timestamp: u64,
+    pub frontmatter: PromptFrontmatter,
+    pub content: String,
+}
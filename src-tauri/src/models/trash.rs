@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    pub id: String,
+    /// 删除前的完整路径
+    pub original_path: String,
+    /// 删除前所在的 prompt 目录（favorites/templates）；通过 delete_file 删除的任意文件为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_folder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_id: Option<String>,
+    pub title: String,
+    pub deleted_at: String,
+    /// Unix 秒，用于自动清除
+    pub deleted_timestamp: u64,
+}
@@ -0,0 +1,65 @@
+use super::TagCount;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageAction {
+    Copy,
+    Polish,
+    Render,
+}
+
+/// 使用事件日志中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageEvent {
+    pub prompt_id: String,
+    pub folder: String,
+    pub action: UsageAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    pub timestamp: u64,
+    /// 压缩后的记录代表同一天内的多次使用，`timestamp` 为其中最晚的一次
+    #[serde(default = "single_use", skip_serializing_if = "is_single_use")]
+    pub count: u32,
+}
+
+fn single_use() -> u32 {
+    1
+}
+
+fn is_single_use(count: &u32) -> bool {
+    *count == 1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    /// 桶起始时间（Unix 秒，UTC）
+    pub start: u64,
+    /// 桶起始日期 YYYY-MM-DD
+    pub date: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptUsage {
+    pub id: String,
+    pub folder: String,
+    pub title: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub from: u64,
+    pub to: u64,
+    pub total: u32,
+    pub timeline: Vec<UsageBucket>,
+    pub top_prompts: Vec<PromptUsage>,
+    /// 统计区间内没有任何使用记录的 prompt
+    pub unused_prompts: Vec<PromptUsage>,
+    pub top_tags: Vec<TagCount>,
+}
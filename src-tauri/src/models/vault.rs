@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// `vault/.vault.json`：派生密钥所需的参数，以及用于校验口令的密文；不包含密钥本身
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultMeta {
+    pub version: u32,
+    /// 十六进制
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// 已知明文的密文（十六进制），能解密即说明口令正确
+    pub check: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    /// 是否已创建保险库
+    pub initialized: bool,
+    pub unlocked: bool,
+    /// 保险库中的 prompt 数量；锁定时也能统计，不需要解密
+    pub item_count: usize,
+}
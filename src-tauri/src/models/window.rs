@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 窗口的物理像素位置与内容区尺寸
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 按显示器布局分别记录的窗口位置，接入外接显示器时不会把窗口放到不存在的屏幕上
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+    #[serde(default)]
+    pub layouts: HashMap<String, WindowGeometry>,
+}
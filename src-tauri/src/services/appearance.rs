@@ -0,0 +1,95 @@
+use crate::models::UIConfig;
+use tauri::{AppHandle, Manager, Theme, WebviewWindow};
+
+// 透明度过低时窗口几乎不可见，用户无法再找回设置
+const MIN_OPACITY: u32 = 30;
+
+/// "system" 或未知取值时返回 None，由系统决定并跟随系统切换
+fn native_theme(theme: &str) -> Option<Theme> {
+    match theme {
+        "light" => Some(Theme::Light),
+        "dark" => Some(Theme::Dark),
+        _ => None,
+    }
+}
+
+/// 按配置设置主窗口的原生明暗外观与透明度；平台不支持的效果只记录日志
+pub fn apply_appearance(app_handle: &AppHandle, ui: &UIConfig) {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+
+    let theme = native_theme(&ui.theme);
+    if let Err(e) = window.set_theme(theme) {
+        eprintln!("[PromptFlow] Failed to set window theme: {}", e);
+    }
+
+    let dark = match theme {
+        Some(theme) => theme == Theme::Dark,
+        None => window.theme().map(|t| t == Theme::Dark).unwrap_or(true),
+    };
+    let opacity = f64::from(ui.opacity.clamp(MIN_OPACITY, 100)) / 100.0;
+
+    // 原生窗口属性只能在主线程修改
+    let target = window.clone();
+    let result = window.run_on_main_thread(move || {
+        if let Err(e) = apply_opacity(&target, opacity, dark) {
+            eprintln!("[PromptFlow] Window opacity is not fully supported: {}", e);
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("[PromptFlow] Failed to apply window opacity: {}", e);
+    }
+}
+
+/// 主题为 "system" 时，系统明暗切换后重新应用效果（Windows 的 acrylic 底色需要跟着变）
+pub fn on_system_theme_changed(app_handle: &AppHandle, ui: &UIConfig) {
+    if native_theme(&ui.theme).is_none() {
+        apply_appearance(app_handle, ui);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn apply_opacity(window: &WebviewWindow, opacity: f64, _dark: bool) -> Result<(), String> {
+    use cocoa::base::id;
+    use objc::{msg_send, sel, sel_impl};
+    use window_vibrancy::{apply_vibrancy, clear_vibrancy, NSVisualEffectMaterial};
+
+    let ns_window = window.ns_window().map_err(|e| e.to_string())? as id;
+    unsafe {
+        let _: () = msg_send![ns_window, setAlphaValue: opacity];
+    }
+
+    if opacity < 1.0 {
+        apply_vibrancy(window, NSVisualEffectMaterial::HudWindow, None, None).map_err(|e| e.to_string())
+    } else {
+        clear_vibrancy(window).map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn apply_opacity(window: &WebviewWindow, opacity: f64, dark: bool) -> Result<(), String> {
+    use window_vibrancy::{apply_acrylic, apply_blur, clear_acrylic, clear_blur};
+
+    if opacity >= 1.0 {
+        let _ = clear_blur(window);
+        return clear_acrylic(window).map_err(|e| e.to_string());
+    }
+
+    let alpha = (opacity * 255.0).round() as u8;
+    let tint = if dark { (18, 18, 20, alpha) } else { (245, 245, 247, alpha) };
+
+    // acrylic 需要 Windows 10 1809 以上，旧系统退回到模糊效果
+    apply_acrylic(window, Some(tint))
+        .or_else(|_| apply_blur(window, Some(tint)))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn apply_opacity(_window: &WebviewWindow, opacity: f64, _dark: bool) -> Result<(), String> {
+    // Linux 下由前端根据 opacity 调整背景透明度
+    if opacity < 1.0 {
+        return Err("native window opacity is not available on this platform".to_string());
+    }
+    Ok(())
+}
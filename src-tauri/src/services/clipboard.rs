@@ -0,0 +1,81 @@
+use super::window::hide_main_window;
+use crate::utils::AppError;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+// 隐藏窗口后等待焦点回到之前的应用
+const FOCUS_RETURN_DELAY: Duration = Duration::from_millis(150);
+// 目标应用读取剪贴板需要一点时间，过早恢复会粘贴出旧内容
+const RESTORE_DELAY: Duration = Duration::from_millis(400);
+
+fn clipboard_error(key: &'static str, e: impl std::fmt::Display) -> AppError {
+    AppError::localized("CLIPBOARD_ERROR", key, &[&e])
+}
+
+pub fn read_text(app_handle: &AppHandle) -> Result<String, AppError> {
+    app_handle.clipboard().read_text().map_err(|e| clipboard_error("clipboard_read_failed", e))
+}
+
+pub fn write_text(app_handle: &AppHandle, text: &str) -> Result<(), AppError> {
+    app_handle
+        .clipboard()
+        .write_text(text)
+        .map_err(|e| clipboard_error("clipboard_write_failed", e))
+}
+
+fn paste_error(e: impl std::fmt::Display) -> AppError {
+    AppError::localized("PASTE_FAILED", "paste_failed", &[&e])
+}
+
+fn send_paste_shortcut() -> Result<(), AppError> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(paste_error)?;
+
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+
+    enigo.key(modifier, Direction::Press).map_err(paste_error)?;
+    let result = enigo.key(Key::Unicode('v'), Direction::Click);
+    // 无论粘贴是否成功都要松开修饰键，避免修饰键卡住
+    enigo.key(modifier, Direction::Release).map_err(paste_error)?;
+    result.map_err(paste_error)
+}
+
+// hide_main_window 把原生窗口操作派发到主线程；主线程任务按顺序执行，
+// 随后派发的任务执行时窗口已经隐藏，焦点开始切回
+fn hide_main_window_and_wait(app_handle: &AppHandle) -> Result<(), AppError> {
+    hide_main_window(app_handle);
+
+    let (done_tx, done_rx) = mpsc::channel();
+    app_handle
+        .run_on_main_thread(move || {
+            let _ = done_tx.send(());
+        })
+        .map_err(paste_error)?;
+    done_rx.recv().map_err(paste_error)
+}
+
+/// 写入剪贴板、隐藏窗口并向之前聚焦的应用发送粘贴快捷键，完成后恢复原剪贴板内容
+pub fn paste_into_previous_app(app_handle: &AppHandle, text: &str) -> Result<(), AppError> {
+    // 剪贴板里可能是图片等非文本内容，此时无法恢复，只能保留粘贴的文本
+    let previous = read_text(app_handle).ok();
+
+    write_text(app_handle, text)?;
+    hide_main_window_and_wait(app_handle)?;
+    thread::sleep(FOCUS_RETURN_DELAY);
+
+    // 粘贴失败时保留剪贴板中的文本，用户仍可手动粘贴
+    send_paste_shortcut()?;
+
+    if let Some(previous) = previous {
+        thread::sleep(RESTORE_DELAY);
+        write_text(app_handle, &previous)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use super::window::show_main_window;
+use crate::commands::file::{ensure_directories, sanitize_filename, PROMPT_FOLDERS};
+use crate::models::DeepLinkAction;
+use crate::utils::{parse_deep_link, AppError};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use url::Url;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkFailed {
+    pub url: String,
+    pub code: String,
+    pub message: String,
+}
+
+/// 未指定目录时按 PROMPT_FOLDERS 顺序查找 prompt 所在目录
+fn resolve_folder(app_handle: &AppHandle, id: &str, folder: Option<String>) -> Result<String, AppError> {
+    let data_dir = ensure_directories(app_handle)?;
+    let candidates: Vec<String> = match folder {
+        Some(folder) => vec![folder],
+        None => PROMPT_FOLDERS.iter().map(|f| f.to_string()).collect(),
+    };
+
+    candidates
+        .into_iter()
+        .find(|folder| data_dir.join(folder).join(format!("{}.md", id)).is_file())
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "prompt_not_found", &[&id]))
+}
+
+fn route(app_handle: &AppHandle, action: DeepLinkAction) -> Result<(), AppError> {
+    match action {
+        DeepLinkAction::Open { id, folder } => {
+            let folder = resolve_folder(app_handle, &id, folder)?;
+            show_main_window(app_handle);
+            let _ = app_handle.emit("deep-link-open", DeepLinkAction::Open { id, folder: Some(folder) });
+        }
+        DeepLinkAction::Render { id, folder, variables } => {
+            let folder = resolve_folder(app_handle, &id, folder)?;
+            show_main_window(app_handle);
+            let _ = app_handle.emit(
+                "deep-link-render",
+                DeepLinkAction::Render { id, folder: Some(folder), variables },
+            );
+        }
+        // 任意网页都能触发链接，这里只把内容交给前端确认，用户确认后再调用 create_prompt
+        DeepLinkAction::New { ref title, ref folder, .. } => {
+            if !PROMPT_FOLDERS.contains(&folder.as_str()) {
+                return Err(AppError::localized("VALIDATION_ERROR", "unknown_folder", &[folder]));
+            }
+            let data_dir = ensure_directories(app_handle)?;
+            let id = sanitize_filename(title);
+            if data_dir.join(folder).join(format!("{}.md", id)).exists() {
+                return Err(AppError::localized("VALIDATION_ERROR", "prompt_exists", &[&format!("{}/{}", folder, id)]));
+            }
+            show_main_window(app_handle);
+            let _ = app_handle.emit("deep-link-new", action);
+        }
+    }
+    Ok(())
+}
+
+/// 处理系统转发过来的 promptflow:// 链接；无效链接通过 deep-link-failed 事件通知前端
+pub fn handle_deep_links(app_handle: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        let result = parse_deep_link(url.as_str()).and_then(|action| route(app_handle, action));
+        if let Err(e) = result {
+            eprintln!("[PromptFlow] Rejected deep link {}: {}", url, e);
+            let _ = app_handle.emit(
+                "deep-link-failed",
+                DeepLinkFailed {
+                    url: url.to_string(),
+                    code: e.code,
+                    message: e.message,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use crate::models::{GitFileChange, GitStatus, GitSyncResult};
+use crate::utils::{write_atomic, AppError};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const REMOTE_NAME: &str = "origin";
+const FALLBACK_AUTHOR_NAME: &str = "PromptFlow";
+const FALLBACK_AUTHOR_EMAIL: &str = "promptflow@localhost";
+
+// 仓库中不应同步的本地文件：应用配置（含 API Key 与服务令牌）、本地历史记录、回收站、
+// 模板包状态以及 write_atomic 的临时文件
+const LOCAL_EXCLUDES: [&str; 9] = [
+    "config.json",
+    "window_state.json",
+    ".history/",
+    ".polish_history.jsonl",
+    ".usage.jsonl",
+    ".trash/",
+    ".packs.json",
+    ".packs/",
+    ".*.tmp",
+];
+
+const CONFLICT_CODES: [&str; 7] = ["DD", "AU", "UD", "UA", "DU", "AA", "UU"];
+
+/// 通过系统 git 命令操作 prompt 仓库；远程地址可以是 URL 或本地裸仓库路径
+pub struct GitRepo {
+    path: PathBuf,
+}
+
+impl GitRepo {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.path.join(".git").exists()
+    }
+
+    pub fn init(&self, branch: &str) -> Result<(), AppError> {
+        if !self.is_initialized() {
+            self.run(&["init"])?;
+            self.run(&["symbolic-ref", "HEAD", &format!("refs/heads/{}", branch)])?;
+        }
+        self.ensure_local_excludes()
+    }
+
+    /// 写入 .git/info/exclude 而不是 .gitignore，避免与远程仓库中的 .gitignore 冲突；
+    /// 仓库可能是用户手动创建的，所以每次提交前都要检查
+    fn ensure_local_excludes(&self) -> Result<(), AppError> {
+        // .git 也可能是指向其他目录的文件（worktree、子模块），交给 git 解析实际路径
+        let exclude_path = self.path.join(self.run(&["rev-parse", "--git-path", "info/exclude"])?.trim());
+        let existing = fs::read_to_string(&exclude_path).unwrap_or_default();
+        let missing: Vec<&str> = LOCAL_EXCLUDES
+            .iter()
+            .copied()
+            .filter(|pattern| !existing.lines().any(|l| l.trim() == *pattern))
+            .collect();
+
+        if !missing.is_empty() {
+            let mut content = existing;
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&missing.join("\n"));
+            content.push('\n');
+            write_atomic(&exclude_path, content)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_remote(&self, url: &str) -> Result<(), AppError> {
+        let current = self.run(&["remote", "get-url", REMOTE_NAME]);
+        match current {
+            Ok(existing) if existing.trim() == url => Ok(()),
+            Ok(_) => self.run(&["remote", "set-url", REMOTE_NAME, url]).map(|_| ()),
+            Err(_) => self.run(&["remote", "add", REMOTE_NAME, url]).map(|_| ()),
+        }
+    }
+
+    /// 暂存所有变更并提交；没有变更时返回 Ok(false)
+    pub fn commit_all(&self, message: &str) -> Result<bool, AppError> {
+        // 合并中途提交会把冲突标记当作合并结果提交
+        if self.is_merging() {
+            return Err(AppError::localized("GIT_ERROR", "git_merge_in_progress", &[]));
+        }
+        self.ensure_local_excludes()?;
+        self.run(&["add", "-A"])?;
+
+        // 排除规则对已跟踪的文件无效，之前误提交的本地文件需要从索引中移除（工作区保留）
+        let tracked = self.run(&["ls-files", "-c", "-i", "--exclude-standard", "-z"])?;
+        let tracked: Vec<&str> = tracked.split('\0').filter(|p| !p.is_empty()).collect();
+        if !tracked.is_empty() {
+            let mut args = vec!["rm", "--cached", "-q", "--"];
+            args.extend(tracked);
+            self.run(&args)?;
+        }
+
+        let staged = self.output(&["diff", "--cached", "--quiet"])?;
+        if staged.status.success() {
+            return Ok(false);
+        }
+
+        let mut cmd = self.command(&["commit", "-q", "-m", message]);
+        self.apply_fallback_identity(&mut cmd);
+        Self::check(cmd.output()?)?;
+
+        Ok(true)
+    }
+
+    pub fn status(&self) -> Result<GitStatus, AppError> {
+        if !self.is_initialized() {
+            return Ok(GitStatus {
+                initialized: false,
+                branch: None,
+                upstream: None,
+                ahead: 0,
+                behind: 0,
+                changes: vec![],
+                conflicts: vec![],
+            });
+        }
+
+        let output = self.run(&["status", "--porcelain=v1", "--branch", "--untracked-files=all"])?;
+        Ok(parse_porcelain_status(&output))
+    }
+
+    pub fn pull(&self, branch: &str) -> Result<GitSyncResult, AppError> {
+        let mut cmd = self.command(&["pull", "--no-rebase", "--no-edit", REMOTE_NAME, branch]);
+        self.apply_fallback_identity(&mut cmd);
+        let output = cmd
+            .output()
+            .map_err(|e| AppError::localized("GIT_ERROR", "git_run_failed", &[&e]))?;
+        let text = Self::combined_output(&output);
+
+        if output.status.success() {
+            return Ok(GitSyncResult { success: true, output: text, conflicts: vec![] });
+        }
+
+        // prompt 文件的合并冲突交给界面处理；其他文件冲突界面无法解决，
+        // 直接放弃合并，不让仓库停在合并中途
+        let conflicts = self.conflicted_files()?;
+        if !conflicts.is_empty() && conflicts.iter().all(|f| f.ends_with(".md")) {
+            return Ok(GitSyncResult { success: false, output: text, conflicts });
+        }
+        if self.is_merging() {
+            self.abort_merge()?;
+        }
+        if conflicts.is_empty() {
+            return Err(AppError::git_error(text));
+        }
+        Err(AppError::localized("GIT_ERROR", "git_merge_aborted", &[&conflicts.join(", ")]))
+    }
+
+    pub fn push(&self, branch: &str) -> Result<GitSyncResult, AppError> {
+        let output = self.output(&["push", "-u", REMOTE_NAME, branch])?;
+        let text = Self::combined_output(&output);
+
+        if !output.status.success() {
+            return Err(AppError::git_error(text));
+        }
+
+        Ok(GitSyncResult { success: true, output: text, conflicts: vec![] })
+    }
+
+    pub fn abort_merge(&self) -> Result<(), AppError> {
+        self.run(&["merge", "--abort"]).map(|_| ())
+    }
+
+    fn is_merging(&self) -> bool {
+        self.run(&["rev-parse", "-q", "--verify", "MERGE_HEAD"]).is_ok()
+    }
+
+    fn conflicted_files(&self) -> Result<Vec<String>, AppError> {
+        let output = self.run(&["diff", "--name-only", "--diff-filter=U"])?;
+        Ok(output
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.arg("-C")
+            .arg(&self.path)
+            .args(["-c", "core.quotePath=false"])
+            .args(args);
+        // 不允许 git 弹出交互式凭据输入，否则后台调用会卡住
+        cmd.env("GIT_TERMINAL_PROMPT", "0");
+        cmd
+    }
+
+    // 未配置 user.email 时 git 会拒绝提交/合并，使用应用身份兜底
+    fn apply_fallback_identity(&self, cmd: &mut Command) {
+        let configured = self
+            .run(&["config", "user.email"])
+            .map(|v| !v.trim().is_empty())
+            .unwrap_or(false);
+        if !configured {
+            cmd.env("GIT_AUTHOR_NAME", FALLBACK_AUTHOR_NAME)
+                .env("GIT_AUTHOR_EMAIL", FALLBACK_AUTHOR_EMAIL)
+                .env("GIT_COMMITTER_NAME", FALLBACK_AUTHOR_NAME)
+                .env("GIT_COMMITTER_EMAIL", FALLBACK_AUTHOR_EMAIL);
+        }
+    }
+
+    fn output(&self, args: &[&str]) -> Result<Output, AppError> {
+        self.command(args)
+            .output()
+            .map_err(|e| AppError::localized("GIT_ERROR", "git_run_failed", &[&e]))
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, AppError> {
+        Self::check(self.output(args)?)
+    }
+
+    fn check(output: Output) -> Result<String, AppError> {
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(AppError::git_error(Self::combined_output(&output)))
+        }
+    }
+
+    fn combined_output(output: &Output) -> String {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        format!("{}{}", stdout, stderr).trim().to_string()
+    }
+}
+
+fn parse_porcelain_status(output: &str) -> GitStatus {
+    let mut status = GitStatus {
+        initialized: true,
+        branch: None,
+        upstream: None,
+        ahead: 0,
+        behind: 0,
+        changes: vec![],
+        conflicts: vec![],
+    };
+
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("## ") {
+            parse_branch_header(header, &mut status);
+            continue;
+        }
+
+        if line.len() < 4 {
+            continue;
+        }
+
+        let code = &line[..2];
+        let path = line[3..].trim_matches('"').to_string();
+
+        if CONFLICT_CODES.contains(&code) {
+            status.conflicts.push(path.clone());
+        }
+        status.changes.push(GitFileChange { path, status: code.to_string() });
+    }
+
+    status
+}
+
+// 形如 "main...origin/main [ahead 1, behind 2]" 或 "No commits yet on main"
+fn parse_branch_header(header: &str, status: &mut GitStatus) {
+    let (names, tracking) = match header.split_once(" [") {
+        Some((names, rest)) => (names, Some(rest.trim_end_matches(']'))),
+        None => (header, None),
+    };
+
+    let names = names
+        .strip_prefix("No commits yet on ")
+        .or_else(|| names.strip_prefix("Initial commit on "))
+        .unwrap_or(names);
+
+    match names.split_once("...") {
+        Some((branch, upstream)) => {
+            status.branch = Some(branch.to_string());
+            status.upstream = Some(upstream.to_string());
+        }
+        None => status.branch = Some(names.to_string()),
+    }
+
+    for part in tracking.unwrap_or_default().split(", ") {
+        if let Some(n) = part.strip_prefix("ahead ") {
+            status.ahead = n.parse().unwrap_or(0);
+        } else if let Some(n) = part.strip_prefix("behind ") {
+            status.behind = n.parse().unwrap_or(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("promptflow-git-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        GitRepo::new(dir).run(args).unwrap()
+    }
+
+    fn tracked_files(dir: &Path) -> Vec<String> {
+        git(dir, &["ls-files"]).lines().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_branch_header() {
+        let mut status = parse_porcelain_status("");
+        parse_branch_header("main...origin/main [ahead 1, behind 2]", &mut status);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (1, 2));
+
+        let mut status = parse_porcelain_status("");
+        parse_branch_header("No commits yet on trunk", &mut status);
+        assert_eq!(status.branch.as_deref(), Some("trunk"));
+        assert_eq!(status.upstream, None);
+        assert_eq!((status.ahead, status.behind), (0, 0));
+
+        let mut status = parse_porcelain_status("");
+        parse_branch_header("main...origin/main [behind 3]", &mut status);
+        assert_eq!((status.ahead, status.behind), (0, 3));
+    }
+
+    #[test]
+    fn parses_porcelain_changes_and_conflicts() {
+        let output = "## main...origin/main [ahead 1]\n M favorites/a.md\n?? \"templates/新 建.md\"\nUU favorites/b.md\nAA c.md\n";
+        let status = parse_porcelain_status(output);
+
+        assert!(status.initialized);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.ahead, 1);
+        let changes: Vec<(&str, &str)> = status.changes.iter().map(|c| (c.status.as_str(), c.path.as_str())).collect();
+        assert_eq!(
+            changes,
+            vec![
+                (" M", "favorites/a.md"),
+                ("??", "templates/新 建.md"),
+                ("UU", "favorites/b.md"),
+                ("AA", "c.md"),
+            ]
+        );
+        assert_eq!(status.conflicts, vec!["favorites/b.md", "c.md"]);
+    }
+
+    #[test]
+    fn commit_skips_local_files_in_existing_repo() {
+        let dir = temp_dir("existing");
+        // 用户自己创建的仓库，而且已经提交过 config.json
+        git(&dir, &["init", "-q"]);
+        fs::write(dir.join("config.json"), "{\"apiKey\":\"secret\"}").unwrap();
+        let repo = GitRepo::new(&dir);
+        git(&dir, &["add", "config.json"]);
+        let mut cmd = repo.command(&["commit", "-q", "-m", "Initial"]);
+        repo.apply_fallback_identity(&mut cmd);
+        GitRepo::check(cmd.output().unwrap()).unwrap();
+
+        fs::create_dir_all(dir.join("favorites")).unwrap();
+        fs::write(dir.join("favorites").join("a.md"), "a").unwrap();
+        fs::write(dir.join(".usage.jsonl"), "{}").unwrap();
+        fs::write(dir.join(".packs.json"), "{}").unwrap();
+        fs::write(dir.join("favorites").join(".a.md.tmp"), "partial").unwrap();
+
+        assert!(repo.commit_all("Add a").unwrap());
+        assert_eq!(tracked_files(&dir), vec!["favorites/a.md"]);
+        assert!(dir.join("config.json").is_file());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn syncs_through_bare_remote() {
+        let base = temp_dir("sync");
+        let remote = base.join("remote.git");
+        git(&base, &["init", "-q", "--bare", remote.to_str().unwrap()]);
+        let remote = remote.to_string_lossy().to_string();
+
+        let a_dir = base.join("a");
+        let b_dir = base.join("b");
+        fs::create_dir_all(&a_dir).unwrap();
+        fs::create_dir_all(&b_dir).unwrap();
+        let a = GitRepo::new(&a_dir);
+        let b = GitRepo::new(&b_dir);
+        for repo in [&a, &b] {
+            repo.init("main").unwrap();
+            repo.set_remote(&remote).unwrap();
+        }
+
+        fs::write(a_dir.join("x.md"), "one\n").unwrap();
+        fs::write(a_dir.join("config.json"), "{}").unwrap();
+        assert!(a.commit_all("Add x").unwrap());
+        assert!(!a.commit_all("Nothing").unwrap());
+        assert!(a.push("main").unwrap().success);
+
+        assert!(b.pull("main").unwrap().success);
+        assert_eq!(fs::read_to_string(b_dir.join("x.md")).unwrap(), "one\n");
+        assert!(!b_dir.join("config.json").exists());
+
+        // 两边同时修改同一个 prompt：冲突交给界面，放弃后回到合并前的状态
+        fs::write(b_dir.join("x.md"), "two\n").unwrap();
+        b.commit_all("Edit in b").unwrap();
+        b.push("main").unwrap();
+        fs::write(a_dir.join("x.md"), "three\n").unwrap();
+        a.commit_all("Edit in a").unwrap();
+
+        let result = a.pull("main").unwrap();
+        assert!(!result.success);
+        assert_eq!(result.conflicts, vec!["x.md"]);
+        assert_eq!(a.status().unwrap().conflicts, vec!["x.md"]);
+        // 冲突未解决时不能提交，否则冲突标记会成为合并结果
+        let head = git(&a_dir, &["rev-parse", "HEAD"]);
+        let err = a.commit_all("Auto-commit during merge").unwrap_err();
+        assert_eq!(err.key, Some("git_merge_in_progress"));
+        assert_eq!(git(&a_dir, &["rev-parse", "HEAD"]), head);
+        assert!(a.is_merging());
+        a.abort_merge().unwrap();
+        assert!(!a.is_merging());
+        assert_eq!(fs::read_to_string(a_dir.join("x.md")).unwrap(), "three\n");
+
+        // 非 prompt 文件冲突时直接放弃合并
+        fs::write(b_dir.join("notes.txt"), "b\n").unwrap();
+        b.commit_all("Notes in b").unwrap();
+        b.push("main").unwrap();
+        fs::write(a_dir.join("x.md"), "two\n").unwrap();
+        fs::write(a_dir.join("notes.txt"), "a\n").unwrap();
+        a.commit_all("Notes in a").unwrap();
+
+        let err = a.pull("main").unwrap_err();
+        assert_eq!(err.code, "GIT_ERROR");
+        assert_eq!(err.key, Some("git_merge_aborted"));
+        assert!(!a.is_merging());
+        assert!(a.status().unwrap().conflicts.is_empty());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,20 @@
+use super::window::show_main_window;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// 第二次启动时转发给已运行实例的参数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondInstancePayload {
+    pub args: Vec<String>,
+    pub cwd: String,
+}
+
+/// 再次启动应用时由 single-instance 插件回调：聚焦已有窗口并把命令行参数转发给前端
+pub fn on_second_instance(app_handle: &AppHandle, args: Vec<String>, cwd: String) {
+    show_main_window(app_handle);
+
+    // 第一个参数是可执行文件路径
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    let _ = app_handle.emit("second-instance", SecondInstancePayload { args, cwd });
+}
@@ -1,6 +1,13 @@
 pub mod shortcut;
 pub mod accessibility;
 pub mod window;
+pub mod git;
+pub mod clipboard;
+pub mod appearance;
+pub mod tray;
+pub mod instance;
+pub mod deep_link;
+pub mod server;
 
 #[cfg(target_os = "macos")]
 pub mod panel;
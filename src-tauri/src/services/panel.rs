@@ -83,7 +83,6 @@ pub fn init_panel(app_handle: &AppHandle) {
 }
 
 /// 显示 panel
-#[allow(dead_code)]
 pub fn show_panel(app_handle: &AppHandle) {
     if let Ok(panel) = app_handle.get_webview_panel("main") {
         panel.show();
@@ -91,13 +90,20 @@ pub fn show_panel(app_handle: &AppHandle) {
 }
 
 /// 隐藏 panel
-#[allow(dead_code)]
 pub fn hide_panel(app_handle: &AppHandle) {
     if let Ok(panel) = app_handle.get_webview_panel("main") {
         panel.hide();
     }
 }
 
+/// panel 当前是否可见
+pub fn is_panel_visible(app_handle: &AppHandle) -> bool {
+    app_handle
+        .get_webview_panel("main")
+        .map(|panel| panel.is_visible())
+        .unwrap_or(false)
+}
+
 /// 切换 panel 显示/隐藏状态
 #[allow(dead_code)]
 pub fn toggle_panel(app_handle: &AppHandle) {
@@ -0,0 +1,486 @@
+//! 本地 HTTP/MCP 服务：供 IDE agent 和脚本读取、渲染 prompt
+//!
+//! REST:
+//!   GET  /api/prompts?folder=&q=
+//!   GET  /api/prompts/{folder}/{id}
+//!   POST /api/prompts/{folder}/{id}/render   {"variables": {...}}
+//! MCP (JSON-RPC over HTTP):
+//!   POST /mcp   initialize / ping / prompts/list / prompts/get
+
+use crate::commands::file::{ensure_directories, load_all_prompts};
+use crate::commands::usage::{apply_usage_stats, log_usage};
+use crate::models::{PromptItem, RenderedPrompt, ServerConfig, UsageAction};
+use crate::utils::{extract_variables, missing_variables, prompt_matches, render_template, AppError};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+const MCP_PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+/// 当前运行的服务；令牌变化时原地替换，端口变化时重新监听
+#[derive(Default)]
+pub struct ServerState {
+    running: Mutex<Option<RunningServer>>,
+}
+
+struct RunningServer {
+    server: Arc<Server>,
+    worker: JoinHandle<()>,
+    port: u16,
+    token: Arc<RwLock<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub url: Option<String>,
+    pub mcp_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RenderRequest {
+    #[serde(default)]
+    variables: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+fn server_error(key: &'static str, args: &[&dyn std::fmt::Display]) -> AppError {
+    AppError::localized("SERVER_ERROR", key, args)
+}
+
+/// 生成 32 字节随机令牌（hex）
+pub fn generate_server_token() -> Result<String, AppError> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| server_error("token_generate_failed", &[&e]))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 按配置启动、更新或停止服务
+pub fn apply_server_config(app: &AppHandle, config: &ServerConfig) -> Result<(), AppError> {
+    let state = app
+        .try_state::<ServerState>()
+        .ok_or_else(|| server_error("server_not_initialized", &[]))?;
+    let mut running = state.running.lock().map_err(|_| server_error("server_state_poisoned", &[]))?;
+
+    if let Some(current) = running.as_ref() {
+        if config.enabled && current.port == config.port {
+            if let Ok(mut token) = current.token.write() {
+                token.clone_from(&config.token);
+            }
+            return Ok(());
+        }
+    }
+    // 等旧的工作线程退出、释放监听端口后再重新监听，否则同一端口可能绑定失败
+    if let Some(previous) = running.take() {
+        previous.server.unblock();
+        let _ = previous.worker.join();
+    }
+
+    if !config.enabled {
+        return Ok(());
+    }
+    if config.token.is_empty() {
+        return Err(AppError::localized("VALIDATION_ERROR", "server_token_empty", &[]));
+    }
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+    let server = Server::http(addr)
+        .map(Arc::new)
+        .map_err(|e| server_error("server_listen_failed", &[&addr, &e]))?;
+    let token = Arc::new(RwLock::new(config.token.clone()));
+
+    let listener = server.clone();
+    let worker_token = token.clone();
+    let app_handle = app.clone();
+    let port = config.port;
+    let worker = thread::spawn(move || {
+        for request in listener.incoming_requests() {
+            handle_request(&app_handle, &worker_token, port, request);
+        }
+    });
+
+    *running = Some(RunningServer {
+        server,
+        worker,
+        port: config.port,
+        token,
+    });
+    Ok(())
+}
+
+pub fn server_status(app: &AppHandle) -> ServerStatus {
+    let port = app
+        .try_state::<ServerState>()
+        .and_then(|state| state.running.lock().ok().and_then(|r| r.as_ref().map(|s| s.port)));
+
+    ServerStatus {
+        running: port.is_some(),
+        port,
+        url: port.map(|p| format!("http://127.0.0.1:{}/api", p)),
+        mcp_url: port.map(|p| format!("http://127.0.0.1:{}/mcp", p)),
+    }
+}
+
+fn handle_request(app: &AppHandle, token: &RwLock<String>, port: u16, mut request: Request) {
+    let response = match authorize(&request, token, port) {
+        Ok(()) => dispatch(app, &mut request),
+        Err(e) => error_response(&e),
+    };
+    let _ = request.respond(response);
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+fn authorize(request: &Request, token: &RwLock<String>, port: u16) -> Result<(), AppError> {
+    // 校验 Host，防止 DNS rebinding 让网页借用 127.0.0.1
+    let host = header(request, "Host").unwrap_or_default();
+    let allowed_hosts = [
+        "127.0.0.1".to_string(),
+        "localhost".to_string(),
+        format!("127.0.0.1:{}", port),
+        format!("localhost:{}", port),
+    ];
+    if !allowed_hosts.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+        return Err(AppError::localized("FORBIDDEN", "host_not_allowed", &[&host]));
+    }
+
+    let expected = token.read().map_err(|_| server_error("server_state_poisoned", &[]))?;
+    let provided = header(request, "Authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if expected.is_empty() || !constant_time_eq(provided.trim().as_bytes(), expected.as_bytes()) {
+        return Err(AppError::localized("UNAUTHORIZED", "token_invalid", &[]));
+    }
+    Ok(())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn dispatch(app: &AppHandle, request: &mut Request) -> HttpResponse {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    let segments: Vec<String> = path
+        .trim_matches('/')
+        .split('/')
+        .map(|s| percent_decode_str(s).decode_utf8_lossy().to_string())
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let method = request.method().clone();
+
+    let result = match (&method, segments.as_slice()) {
+        (Method::Post, ["mcp"]) => return handle_mcp(app, request),
+        (Method::Get, ["api", "prompts"]) => {
+            let params: HashMap<String, String> = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+            list_prompts(app, params.get("folder").map(String::as_str), params.get("q").map(String::as_str))
+                .and_then(|prompts| Ok(serde_json::to_value(prompts)?))
+        }
+        (Method::Get, ["api", "prompts", folder, id]) => {
+            find_prompt(app, folder, id).and_then(|prompt| Ok(serde_json::to_value(prompt)?))
+        }
+        (Method::Post, ["api", "prompts", folder, id, "render"]) => read_body(request)
+            .and_then(|body| {
+                if body.trim().is_empty() {
+                    return Ok(RenderRequest { variables: HashMap::new() });
+                }
+                Ok(serde_json::from_str::<RenderRequest>(&body)?)
+            })
+            .and_then(|input| render_prompt(app, folder, id, &input.variables))
+            .and_then(|rendered| Ok(serde_json::to_value(rendered)?)),
+        (method, _) => Err(AppError::localized("NOT_FOUND", "route_not_found", &[&method, &path])),
+    };
+
+    match result {
+        Ok(value) => json_response(200, &value),
+        Err(e) => error_response(&e),
+    }
+}
+
+fn read_body(request: &mut Request) -> Result<String, AppError> {
+    let mut body = String::new();
+    request.as_reader().take(MAX_BODY_BYTES + 1).read_to_string(&mut body)?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(AppError::localized("VALIDATION_ERROR", "request_too_large", &[]));
+    }
+    Ok(body)
+}
+
+fn json_response(status: u16, value: &Value) -> HttpResponse {
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(content_type)
+}
+
+fn error_response(error: &AppError) -> HttpResponse {
+    let status = match error.code.as_str() {
+        "UNAUTHORIZED" => 401,
+        "FORBIDDEN" => 403,
+        "NOT_FOUND" => 404,
+        "VALIDATION_ERROR" | "PARSE_ERROR" => 400,
+        _ => 500,
+    };
+    json_response(status, &json!(error))
+}
+
+fn list_prompts(app: &AppHandle, folder: Option<&str>, query: Option<&str>) -> Result<Vec<PromptItem>, AppError> {
+    served_prompts(&ensure_directories(app)?, folder, query)
+}
+
+// 持有令牌的任何本地进程都能访问服务，保险库即使已解锁也不对外提供
+fn served_prompts(data_dir: &Path, folder: Option<&str>, query: Option<&str>) -> Result<Vec<PromptItem>, AppError> {
+    let mut prompts: Vec<PromptItem> = load_all_prompts(data_dir, false)?
+        .into_iter()
+        .filter(|p| folder.map(|f| f == p.folder).unwrap_or(true))
+        .filter(|p| query.map(|q| prompt_matches(p, q)).unwrap_or(true))
+        .collect();
+    apply_usage_stats(data_dir, &mut prompts);
+    Ok(prompts)
+}
+
+fn find_prompt(app: &AppHandle, folder: &str, id: &str) -> Result<PromptItem, AppError> {
+    list_prompts(app, Some(folder), None)?
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| AppError::localized("NOT_FOUND", "prompt_not_found", &[&format!("{}/{}", folder, id)]))
+}
+
+fn render_prompt(
+    app: &AppHandle,
+    folder: &str,
+    id: &str,
+    variables: &HashMap<String, String>,
+) -> Result<RenderedPrompt, AppError> {
+    let prompt = find_prompt(app, folder, id)?;
+    log_usage(&ensure_directories(app)?, &prompt.id, &prompt.folder, UsageAction::Render);
+    Ok(RenderedPrompt {
+        content: render_template(&prompt.content, variables),
+        missing_variables: missing_variables(&prompt.content, variables),
+        id: prompt.id,
+        folder: prompt.folder,
+    })
+}
+
+fn handle_mcp(app: &AppHandle, request: &mut Request) -> HttpResponse {
+    let reply = read_body(request)
+        .map(|body| rpc_reply(&body, |method, params| mcp_call(app, method, params)))
+        .unwrap_or_else(|e| Some((400, rpc_error(Value::Null, -32700, &e.message))));
+
+    match reply {
+        Some((status, value)) => json_response(status, &value),
+        None => Response::from_data(Vec::new()).with_status_code(202),
+    }
+}
+
+/// 解析并执行一条 JSON-RPC 请求，返回 HTTP 状态码与响应；通知没有 id，返回 None
+fn rpc_reply(body: &str, call: impl FnOnce(&str, &Value) -> Result<Value, (i64, String)>) -> Option<(u16, Value)> {
+    let rpc = match serde_json::from_str::<RpcRequest>(body) {
+        Ok(rpc) => rpc,
+        Err(e) => return Some((400, rpc_error(Value::Null, -32700, &e.to_string()))),
+    };
+    let id = rpc.id?;
+
+    Some(match call(&rpc.method, &rpc.params) {
+        Ok(result) => (200, json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+        Err((code, message)) => (200, rpc_error(id, code, &message)),
+    })
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// 客户端请求的协议版本不受支持时回退到最新版本
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| MCP_PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(MCP_PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "prompts": { "listChanged": false } },
+        "serverInfo": { "name": "PromptFlow", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn mcp_call(app: &AppHandle, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    let internal = |e: AppError| match e.code.as_str() {
+        "NOT_FOUND" | "VALIDATION_ERROR" => (-32602, e.message),
+        _ => (-32603, e.message),
+    };
+
+    match method {
+        "initialize" => Ok(initialize_result(params)),
+        "ping" => Ok(json!({})),
+        "prompts/list" => {
+            let prompts: Vec<Value> = list_prompts(app, None, None)
+                .map_err(internal)?
+                .iter()
+                .map(|p| {
+                    let arguments: Vec<Value> = extract_variables(&p.content)
+                        .into_iter()
+                        .map(|name| json!({ "name": name, "required": true }))
+                        .collect();
+                    json!({
+                        "name": format!("{}/{}", p.folder, p.id),
+                        "title": p.title,
+                        "description": p.description.clone().unwrap_or_else(|| p.title.clone()),
+                        "arguments": arguments,
+                    })
+                })
+                .collect();
+            Ok(json!({ "prompts": prompts }))
+        }
+        "prompts/get" => {
+            let name = params
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or((-32602, "Missing prompt name".to_string()))?;
+            let (folder, id) = name
+                .split_once('/')
+                .ok_or_else(|| (-32602, format!("Invalid prompt name: {}", name)))?;
+            let variables: HashMap<String, String> = params
+                .get("arguments")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| (-32602, format!("Invalid arguments: {}", e)))?
+                .unwrap_or_default();
+
+            let prompt = find_prompt(app, folder, id).map_err(internal)?;
+            if let Ok(data_dir) = ensure_directories(app) {
+                log_usage(&data_dir, &prompt.id, &prompt.folder, UsageAction::Render);
+            }
+            Ok(json!({
+                "description": prompt.description.clone().unwrap_or_else(|| prompt.title.clone()),
+                "messages": [{
+                    "role": "user",
+                    "content": { "type": "text", "text": render_template(&prompt.content, &variables) },
+                }],
+            }))
+        }
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::vault::{create_vault_at, write_prompt_file, TEST_SESSION_LOCK, VAULT_FOLDER};
+    use tiny_http::TestRequest;
+
+    const PORT: u16 = 38917;
+    const TOKEN: &str = "0123456789abcdef";
+
+    fn request(host: &str, authorization: Option<&str>) -> Request {
+        let mut request = TestRequest::new().with_header(Header::from_bytes("Host", host).unwrap());
+        if let Some(authorization) = authorization {
+            request = request.with_header(Header::from_bytes("Authorization", authorization).unwrap());
+        }
+        request.into()
+    }
+
+    fn check(host: &str, authorization: Option<&str>) -> Result<(), String> {
+        let token = RwLock::new(TOKEN.to_string());
+        authorize(&request(host, authorization), &token, PORT).map_err(|e| e.code)
+    }
+
+    #[test]
+    fn compares_tokens_exactly() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn authorizes_local_hosts_with_the_token() {
+        let bearer = format!("Bearer {}", TOKEN);
+        for host in ["127.0.0.1", "localhost", "127.0.0.1:38917", "LOCALHOST:38917"] {
+            assert_eq!(check(host, Some(&bearer)), Ok(()), "{}", host);
+        }
+        assert_eq!(check("localhost", None), Err("UNAUTHORIZED".to_string()));
+        assert_eq!(check("localhost", Some("Bearer wrong")), Err("UNAUTHORIZED".to_string()));
+        assert_eq!(check("localhost", Some(TOKEN)), Err("UNAUTHORIZED".to_string()));
+    }
+
+    #[test]
+    fn rejects_foreign_hosts_before_checking_the_token() {
+        let bearer = format!("Bearer {}", TOKEN);
+        for host in ["evil.example", "127.0.0.1:80", "localhost.evil.example:38917", ""] {
+            assert_eq!(check(host, Some(&bearer)), Err("FORBIDDEN".to_string()), "{}", host);
+        }
+    }
+
+    #[test]
+    fn answers_json_rpc_requests() {
+        let call = |method: &str, params: &Value| match method {
+            "initialize" => Ok(initialize_result(params)),
+            other => Err((-32601, format!("Method not found: {}", other))),
+        };
+
+        let body = r#"{"jsonrpc":"2.0","id":7,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#;
+        let (status, reply) = rpc_reply(body, call).unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["result"]["protocolVersion"], "2025-03-26");
+
+        let body = r#"{"jsonrpc":"2.0","id":"x","method":"initialize","params":{"protocolVersion":"1999-01-01"}}"#;
+        assert_eq!(rpc_reply(body, call).unwrap().1["result"]["protocolVersion"], MCP_PROTOCOL_VERSIONS[0]);
+
+        let (_, reply) = rpc_reply(r#"{"jsonrpc":"2.0","id":8,"method":"nope"}"#, call).unwrap();
+        assert_eq!(reply["error"]["code"], -32601);
+        assert_eq!(reply["id"], 8);
+
+        assert!(rpc_reply(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#, call).is_none());
+        let (status, reply) = rpc_reply("{not json", call).unwrap();
+        assert_eq!((status, reply["error"]["code"].as_i64()), (400, Some(-32700)));
+    }
+
+    #[test]
+    fn never_serves_vault_prompts() {
+        let _guard = TEST_SESSION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let data_dir = std::env::temp_dir().join(format!("promptflow-server-vault-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&data_dir);
+        std::fs::create_dir_all(data_dir.join("favorites")).unwrap();
+        std::fs::write(data_dir.join("favorites").join("open.md"), "Open prompt").unwrap();
+        create_vault_at(&data_dir, "correct horse battery").unwrap();
+        write_prompt_file(&data_dir.join(VAULT_FOLDER).join("secret.md"), "Secret prompt").unwrap();
+        assert!(load_all_prompts(&data_dir, true).unwrap().iter().any(|p| p.id == "secret"));
+
+        let ids: Vec<String> = served_prompts(&data_dir, None, None).unwrap().into_iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec!["open"]);
+        assert!(served_prompts(&data_dir, Some(VAULT_FOLDER), None).unwrap().is_empty());
+        assert!(served_prompts(&data_dir, None, Some("secret")).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+}
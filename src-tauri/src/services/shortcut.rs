@@ -1,43 +1,466 @@
-use tauri::AppHandle;
+use super::clipboard;
+use super::window::toggle_main_window;
+use crate::commands::file::ensure_directories;
+use crate::commands::usage::log_usage;
+use crate::commands::vault::read_prompt_file;
+use crate::models::{PromptItem, UsageAction};
+use crate::utils::{parse_markdown_prompt, render_template, AppError};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
+/// 当前生效的主快捷键，用于热更新与注册失败时回退
+#[derive(Default)]
+pub struct MainHotkeyState {
+    current: Mutex<Option<(String, Shortcut)>>,
+    // 暂停时所有快捷键都会被注销，恢复时重新注册
+    paused: AtomicBool,
+}
+
+/// 已注册的 prompt 专属快捷键
+#[derive(Default)]
+pub struct PromptHotkeyState {
+    bindings: Mutex<Vec<PromptHotkeyBinding>>,
+}
+
+#[derive(Debug, Clone)]
+struct PromptHotkeyBinding {
+    prompt_id: String,
+    file_path: String,
+    folder: String,
+    shortcut: Shortcut,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptHotkeyConflict {
+    pub prompt_id: String,
+    pub folder: String,
+    pub hotkey: String,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<&'static str>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyRegistrationFailed {
+    pub requested: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<&'static str>,
+    pub message: String,
+}
+
+// macOS: Option + Space
+// Windows/Linux: Ctrl + Space (Alt + Space conflicts with Windows system menu)
 #[cfg(target_os = "macos")]
-use super::panel;
+const PLATFORM_DEFAULT_HOTKEY: &str = "Option+Space";
+#[cfg(not(target_os = "macos"))]
+const PLATFORM_DEFAULT_HOTKEY: &str = "Ctrl+Space";
 
-pub fn setup_global_shortcut(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // macOS: Option + Space
-    // Windows/Linux: Ctrl + Space (Alt + Space conflicts with Windows system menu)
-    #[cfg(target_os = "macos")]
-    let shortcut = Shortcut::new(Some(Modifiers::ALT), Code::Space);
-    
-    #[cfg(not(target_os = "macos"))]
-    let shortcut = Shortcut::new(Some(Modifiers::CONTROL), Code::Space);
-    
-    let app_handle = app.clone();
-    
-    app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, event| {
-        if event.state == ShortcutState::Pressed {
-            eprintln!("[PromptFlow] hotkey fired!");
-            
-            #[cfg(target_os = "macos")]
-            {
-                panel::toggle_panel(&app_handle);
-            }
-            
-            #[cfg(not(target_os = "macos"))]
-            {
-                use tauri::Manager;
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    if window.is_visible().unwrap_or(false) {
-                        let _ = window.hide();
-                    } else {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+/// 解析 "CommandOrControl+Shift+P" 形式的快捷键描述
+pub fn parse_accelerator(accelerator: &str) -> Result<Shortcut, AppError> {
+    let invalid = |key: &'static str| AppError::localized("INVALID_HOTKEY", key, &[&accelerator]);
+
+    let mut modifiers = Modifiers::empty();
+    let mut key: Option<Code> = None;
+
+    for raw in accelerator.split('+') {
+        let token = raw.trim();
+        if token.is_empty() {
+            return Err(invalid("hotkey_empty_segment"));
+        }
+
+        let modifier = match token.to_ascii_lowercase().as_str() {
+            "shift" => Some(Modifiers::SHIFT),
+            "alt" | "option" => Some(Modifiers::ALT),
+            "ctrl" | "control" => Some(Modifiers::CONTROL),
+            "cmd" | "command" | "super" | "meta" | "win" => Some(Modifiers::SUPER),
+            "commandorcontrol" | "commandorctrl" | "cmdorctrl" | "cmdorcontrol" => {
+                if cfg!(target_os = "macos") {
+                    Some(Modifiers::SUPER)
+                } else {
+                    Some(Modifiers::CONTROL)
                 }
             }
+            _ => None,
+        };
+
+        if let Some(modifier) = modifier {
+            if key.is_some() {
+                return Err(invalid("hotkey_modifier_order"));
+            }
+            modifiers |= modifier;
+            continue;
+        }
+
+        if key.is_some() {
+            return Err(invalid("hotkey_multiple_keys"));
         }
-    })?;
-    
+
+        let token = if token.eq_ignore_ascii_case("return") { "Enter" } else { token };
+        let parsed: Shortcut = token
+            .parse()
+            .map_err(|_| AppError::localized("INVALID_HOTKEY", "hotkey_unknown_key", &[&accelerator, &token]))?;
+        key = Some(parsed.key);
+    }
+
+    let key = key.ok_or_else(|| invalid("hotkey_missing_key"))?;
+
+    // 没有修饰键的全局快捷键会吞掉普通输入，只允许功能键单独使用
+    let is_function_key = matches!(
+        key,
+        Code::F1 | Code::F2 | Code::F3 | Code::F4 | Code::F5 | Code::F6 | Code::F7 | Code::F8
+            | Code::F9 | Code::F10 | Code::F11 | Code::F12 | Code::F13 | Code::F14 | Code::F15
+            | Code::F16 | Code::F17 | Code::F18 | Code::F19 | Code::F20 | Code::F21 | Code::F22
+            | Code::F23 | Code::F24
+    );
+    if modifiers.is_empty() && !is_function_key {
+        return Err(invalid("hotkey_missing_modifier"));
+    }
+
+    Ok(Shortcut::new(Some(modifiers), key))
+}
+
+fn register_main_shortcut(app: &AppHandle, shortcut: Shortcut) -> Result<(), AppError> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app_handle, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                eprintln!("[PromptFlow] hotkey fired!");
+                toggle_main_window(app_handle);
+            }
+        })
+        .map_err(|e| AppError::localized("HOTKEY_CONFLICT", "hotkey_register_failed", &[&e]))
+}
+
+/// 启动时注册主快捷键；配置无效或被占用时回退到平台默认快捷键
+pub fn setup_global_shortcut(app: &AppHandle, accelerator: &str) -> Result<(), Box<dyn std::error::Error>> {
+    app.manage(MainHotkeyState::default());
+    app.manage(PromptHotkeyState::default());
+
+    match update_main_hotkey(app, accelerator) {
+        Ok(()) => Ok(()),
+        Err(e) if accelerator != PLATFORM_DEFAULT_HOTKEY => {
+            eprintln!("[PromptFlow] {}; falling back to {}", e, PLATFORM_DEFAULT_HOTKEY);
+            let _ = app.emit(
+                "hotkey-registration-failed",
+                HotkeyRegistrationFailed {
+                    requested: accelerator.to_string(),
+                    fallback: Some(PLATFORM_DEFAULT_HOTKEY.to_string()),
+                    code: e.code.clone(),
+                    key: e.key,
+                    message: e.message.clone(),
+                },
+            );
+            update_main_hotkey(app, PLATFORM_DEFAULT_HOTKEY).map_err(|e| e.into())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 当前注册的主快捷键描述
+pub fn current_main_hotkey(app: &AppHandle) -> Option<String> {
+    let state = app.try_state::<MainHotkeyState>()?;
+    let current = state.current.lock().ok()?;
+    current.as_ref().map(|(accelerator, _)| accelerator.clone())
+}
+
+/// 切换主快捷键：先注册新快捷键，成功后再注销旧的；失败时旧快捷键保持可用
+pub fn update_main_hotkey(app: &AppHandle, accelerator: &str) -> Result<(), AppError> {
+    let shortcut = parse_accelerator(accelerator)?;
+    let state = app.state::<MainHotkeyState>();
+    let mut current = state
+        .current
+        .lock()
+        .map_err(|_| AppError::localized("HOTKEY_CONFLICT", "hotkey_state_poisoned", &[]))?;
+
+    let previous = current.as_ref().map(|(_, s)| *s);
+    if previous == Some(shortcut) || state.paused.load(Ordering::SeqCst) {
+        *current = Some((accelerator.to_string(), shortcut));
+        return Ok(());
+    }
+
+    register_main_shortcut(app, shortcut)?;
+
+    if let Some(previous) = previous {
+        let _ = app.global_shortcut().unregister(previous);
+    }
+    *current = Some((accelerator.to_string(), shortcut));
+
     Ok(())
 }
+
+pub fn hotkeys_paused(app: &AppHandle) -> bool {
+    app.try_state::<MainHotkeyState>()
+        .map(|state| state.paused.load(Ordering::SeqCst))
+        .unwrap_or(false)
+}
+
+/// 暂停或恢复全局快捷键；恢复后调用方需要重新同步 prompt 快捷键
+pub fn set_hotkeys_paused(app: &AppHandle, paused: bool) -> Result<(), AppError> {
+    let Some(state) = app.try_state::<MainHotkeyState>() else {
+        return Ok(());
+    };
+    if state.paused.swap(paused, Ordering::SeqCst) == paused {
+        return Ok(());
+    }
+
+    let shortcut = main_shortcut(app);
+    if paused {
+        if let Some(shortcut) = shortcut {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+        sync_prompt_hotkeys(app, &[]);
+        return Ok(());
+    }
+
+    match shortcut {
+        Some(shortcut) => register_main_shortcut(app, shortcut),
+        None => Ok(()),
+    }
+}
+
+fn main_shortcut(app: &AppHandle) -> Option<Shortcut> {
+    let state = app.try_state::<MainHotkeyState>()?;
+    let current = state.current.lock().ok()?;
+    current.as_ref().map(|(_, shortcut)| *shortcut)
+}
+
+/// 绑定了 `shortcut` 的 prompt；`exclude` 为正在编辑的 prompt 的 (folder, id)，
+/// 不同目录中的 prompt 可以同名，必须同时比较目录
+fn hotkey_owner<'a>(
+    shortcut: Shortcut,
+    exclude: Option<(&str, &str)>,
+    prompts: &'a [PromptItem],
+) -> Option<&'a PromptItem> {
+    prompts
+        .iter()
+        .filter(|p| exclude != Some((p.folder.as_str(), p.id.as_str())))
+        .find(|p| p.hotkey.as_deref().and_then(|h| parse_accelerator(h).ok()) == Some(shortcut))
+}
+
+fn assigned_error(accelerator: &str, owner: &PromptItem) -> AppError {
+    AppError::localized("HOTKEY_CONFLICT", "hotkey_assigned", &[&accelerator, &owner.title])
+}
+
+/// 检查 prompt 快捷键是否有效，且不与主快捷键或其他 prompt 的快捷键冲突
+pub fn validate_prompt_hotkey(
+    app: &AppHandle,
+    accelerator: &str,
+    folder: &str,
+    prompt_id: &str,
+    prompts: &[PromptItem],
+) -> Result<Shortcut, AppError> {
+    let shortcut = parse_accelerator(accelerator)?;
+
+    if main_shortcut(app) == Some(shortcut) {
+        return Err(AppError::localized("HOTKEY_CONFLICT", "hotkey_main_window", &[&accelerator]));
+    }
+
+    match hotkey_owner(shortcut, Some((folder, prompt_id)), prompts) {
+        Some(owner) => Err(assigned_error(accelerator, owner)),
+        None => Ok(shortcut),
+    }
+}
+
+/// 检查新的主快捷键是否有效，且没有被 prompt 占用
+pub fn validate_main_hotkey(accelerator: &str, prompts: &[PromptItem]) -> Result<Shortcut, AppError> {
+    let shortcut = parse_accelerator(accelerator)?;
+    match hotkey_owner(shortcut, None, prompts) {
+        Some(owner) => Err(assigned_error(accelerator, owner)),
+        None => Ok(shortcut),
+    }
+}
+
+// 按下时重新读取文件，保证复制的是最新内容
+fn paste_prompt(app_handle: &AppHandle, binding: &PromptHotkeyBinding) {
+    let prompt = read_prompt_file(Path::new(&binding.file_path))
+        .and_then(|content| parse_markdown_prompt(&content, &binding.file_path, &binding.folder));
+
+    let result = prompt.and_then(|prompt| {
+        let rendered = render_template(&prompt.content, &HashMap::new());
+        clipboard::write_text(app_handle, &rendered)
+    });
+
+    match result {
+        Ok(()) => {
+            if let Ok(data_dir) = ensure_directories(app_handle) {
+                log_usage(&data_dir, &binding.prompt_id, &binding.folder, UsageAction::Copy);
+            }
+            let _ = app_handle.emit("prompt-hotkey-fired", &binding.prompt_id);
+        }
+        Err(e) => eprintln!("[PromptFlow] Failed to copy prompt {}: {}", binding.prompt_id, e),
+    }
+}
+
+/// 根据 prompt 列表重新注册全部 prompt 快捷键，返回无法注册的冲突项
+pub fn sync_prompt_hotkeys(app: &AppHandle, prompts: &[PromptItem]) -> Vec<PromptHotkeyConflict> {
+    let Some(state) = app.try_state::<PromptHotkeyState>() else {
+        return vec![];
+    };
+    let Ok(mut bindings) = state.bindings.lock() else {
+        return vec![];
+    };
+
+    for binding in bindings.drain(..) {
+        let _ = app.global_shortcut().unregister(binding.shortcut);
+    }
+    if hotkeys_paused(app) {
+        return vec![];
+    }
+
+    let main = main_shortcut(app);
+    let mut conflicts = vec![];
+
+    for prompt in prompts {
+        let Some(accelerator) = prompt.hotkey.as_deref() else {
+            continue;
+        };
+
+        let registered = parse_accelerator(accelerator).and_then(|shortcut| {
+            if main == Some(shortcut) || bindings.iter().any(|b| b.shortcut == shortcut) {
+                return Err(AppError::localized("HOTKEY_CONFLICT", "hotkey_in_use", &[&accelerator]));
+            }
+
+            let binding = PromptHotkeyBinding {
+                prompt_id: prompt.id.clone(),
+                file_path: prompt.file_path.clone(),
+                folder: prompt.folder.clone(),
+                shortcut,
+            };
+            let handler_binding = binding.clone();
+            app.global_shortcut()
+                .on_shortcut(shortcut, move |app_handle, _shortcut, event| {
+                    if event.state == ShortcutState::Pressed {
+                        paste_prompt(app_handle, &handler_binding);
+                    }
+                })
+                .map_err(|e| AppError::localized("HOTKEY_CONFLICT", "hotkey_register_failed", &[&e]))?;
+
+            Ok(binding)
+        });
+
+        match registered {
+            Ok(binding) => bindings.push(binding),
+            Err(e) => conflicts.push(PromptHotkeyConflict {
+                prompt_id: prompt.id.clone(),
+                folder: prompt.folder.clone(),
+                hotkey: accelerator.to_string(),
+                code: e.code,
+                key: e.key,
+                message: e.message,
+            }),
+        }
+    }
+
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "macos")]
+    const PRIMARY: Modifiers = Modifiers::SUPER;
+    #[cfg(not(target_os = "macos"))]
+    const PRIMARY: Modifiers = Modifiers::CONTROL;
+
+    #[test]
+    fn parses_default_config_hotkey() {
+        let shortcut = parse_accelerator("CommandOrControl+Shift+P").unwrap();
+        assert_eq!(shortcut, Shortcut::new(Some(PRIMARY | Modifiers::SHIFT), Code::KeyP));
+    }
+
+    #[test]
+    fn parses_aliases_case_insensitively() {
+        assert_eq!(
+            parse_accelerator("option+space").unwrap(),
+            Shortcut::new(Some(Modifiers::ALT), Code::Space)
+        );
+        assert_eq!(
+            parse_accelerator(" Ctrl + Alt + Return ").unwrap(),
+            Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::Enter)
+        );
+        assert_eq!(
+            parse_accelerator("Cmd+1").unwrap(),
+            Shortcut::new(Some(Modifiers::SUPER), Code::Digit1)
+        );
+    }
+
+    #[test]
+    fn allows_bare_function_keys() {
+        assert_eq!(parse_accelerator("F13").unwrap(), Shortcut::new(None, Code::F13));
+    }
+
+    fn prompt(folder: &str, id: &str, hotkey: Option<&str>) -> PromptItem {
+        PromptItem {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            tags: vec![],
+            description: None,
+            use_count: 0,
+            last_used: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+            file_path: format!("{}/{}.md", folder, id),
+            folder: folder.to_string(),
+            hotkey: hotkey.map(|h| h.to_string()),
+            pinned: false,
+            sort_order: None,
+            repository: None,
+            read_only: false,
+            forked_from: None,
+        }
+    }
+
+    #[test]
+    fn compares_hotkey_owners_by_folder_and_id() {
+        let prompts = [
+            prompt("favorites", "review", Some("Ctrl+Alt+R")),
+            prompt("templates", "review", None),
+        ];
+        let shortcut = parse_accelerator("ctrl+alt+r").unwrap();
+
+        assert!(hotkey_owner(shortcut, Some(("favorites", "review")), &prompts).is_none());
+        // 同名但位于另一个目录的 prompt 不能占用同一组合键
+        let owner = hotkey_owner(shortcut, Some(("templates", "review")), &prompts).unwrap();
+        assert_eq!(owner.folder, "favorites");
+    }
+
+    #[test]
+    fn rejects_main_hotkeys_bound_to_prompts() {
+        let prompts = [prompt("favorites", "review", Some("Ctrl+Alt+R"))];
+        let err = validate_main_hotkey("Control+Option+R", &prompts).unwrap_err();
+        assert_eq!(err.code, "HOTKEY_CONFLICT");
+        assert_eq!(err.key, Some("hotkey_assigned"));
+        assert!(validate_main_hotkey("Ctrl+Alt+P", &prompts).is_ok());
+        assert_eq!(validate_main_hotkey("Ctrl+", &prompts).unwrap_err().code, "INVALID_HOTKEY");
+    }
+
+    #[test]
+    fn rejects_invalid_accelerators() {
+        let cases = [
+            ("", "hotkey_empty_segment"),
+            ("Ctrl+", "hotkey_empty_segment"),
+            ("Ctrl+Shift", "hotkey_missing_key"),
+            ("Ctrl+A+B", "hotkey_multiple_keys"),
+            ("A+Ctrl", "hotkey_modifier_order"),
+            ("Ctrl+Foo", "hotkey_unknown_key"),
+            ("P", "hotkey_missing_modifier"),
+        ];
+        for (accelerator, key) in cases {
+            let err = parse_accelerator(accelerator).unwrap_err();
+            assert_eq!(err.code, "INVALID_HOTKEY", "{}", accelerator);
+            assert_eq!(err.key, Some(key), "{}", accelerator);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use super::clipboard;
+use super::shortcut::{hotkeys_paused, set_hotkeys_paused};
+use super::window::{show_main_window, toggle_main_window};
+use crate::commands::file::{ensure_directories, load_all_prompts, refresh_prompt_hotkeys, PROMPT_FOLDERS};
+use crate::commands::usage::{apply_usage_stats, log_usage};
+use crate::commands::vault::{read_prompt_file, VAULT_FOLDER};
+use crate::models::{PromptItem, UsageAction};
+use crate::utils::{parse_markdown_prompt, render_template, tr, AppError};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Wry};
+
+const TRAY_ID: &str = "main";
+const TOP_PROMPTS: usize = 5;
+// 每个目录子菜单最多列出的 prompt 数，其余在主窗口中查看
+const MAX_FOLDER_ITEMS: usize = 20;
+const MAX_TITLE_CHARS: usize = 40;
+// 菜单项 id 格式：prompt:<section>:<folder>/<id>，同一个 prompt 可能出现在多个分组里
+const PROMPT_ITEM_PREFIX: &str = "prompt:";
+const MORE_ITEM_PREFIX: &str = "more:";
+
+struct RefreshState {
+    running: bool,
+    pending: bool,
+}
+
+// 菜单在后台线程重建；重建期间的新请求合并为结束后的一次重建
+static REFRESH: Mutex<RefreshState> = Mutex::new(RefreshState {
+    running: false,
+    pending: false,
+});
+
+fn folder_label(folder: &str) -> String {
+    match folder {
+        "favorites" => tr("tray_favorites", &[]),
+        "templates" => tr("tray_templates", &[]),
+        VAULT_FOLDER => tr("tray_vault", &[]),
+        other => other.to_string(),
+    }
+}
+
+fn menu_title(title: &str) -> String {
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    let truncated: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+    format!("{}…", truncated)
+}
+
+fn prompt_menu_item(app: &AppHandle, section: &str, prompt: &PromptItem) -> tauri::Result<MenuItem<Wry>> {
+    let id = format!("{}{}:{}/{}", PROMPT_ITEM_PREFIX, section, prompt.folder, prompt.id);
+    MenuItem::with_id(app, id, menu_title(&prompt.title), true, None::<&str>)
+}
+
+fn append_section(
+    app: &AppHandle,
+    menu: &Menu<Wry>,
+    section: &str,
+    title: &str,
+    prompts: &[&PromptItem],
+) -> tauri::Result<()> {
+    if prompts.is_empty() {
+        return Ok(());
+    }
+
+    menu.append(&MenuItem::with_id(app, format!("header:{}", section), title, false, None::<&str>)?)?;
+    for prompt in prompts {
+        menu.append(&prompt_menu_item(app, section, prompt)?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    Ok(())
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let prompts = ensure_directories(app)
+        .and_then(|data_dir| {
+            let mut prompts = load_all_prompts(&data_dir, true)?;
+            apply_usage_stats(&data_dir, &mut prompts);
+            Ok(prompts)
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("[PromptFlow] Failed to load prompts for tray: {}", e);
+            vec![]
+        });
+
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(app, "show", tr("tray_show", &[]), true, None::<&str>)?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    let mut most_used: Vec<&PromptItem> = prompts.iter().filter(|p| p.use_count > 0).collect();
+    most_used.sort_by(|a, b| b.use_count.cmp(&a.use_count).then_with(|| a.title.cmp(&b.title)));
+    most_used.truncate(TOP_PROMPTS);
+    append_section(app, &menu, "top", &tr("tray_most_used", &[]), &most_used)?;
+
+    // last_used 是 ISO 8601 字符串，可以直接按字符串排序
+    let mut recent: Vec<&PromptItem> = prompts.iter().filter(|p| p.last_used.is_some()).collect();
+    recent.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    recent.truncate(TOP_PROMPTS);
+    append_section(app, &menu, "recent", &tr("tray_recent", &[]), &recent)?;
+
+    let mut has_folders = false;
+    for folder in PROMPT_FOLDERS.into_iter().chain([VAULT_FOLDER]) {
+        let mut items: Vec<&PromptItem> = prompts.iter().filter(|p| p.folder == folder).collect();
+        if items.is_empty() {
+            continue;
+        }
+        // 置顶的在前，其次是最近使用的
+        items.sort_by(|a, b| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then_with(|| b.last_used.cmp(&a.last_used))
+                .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+        });
+        let hidden = items.len().saturating_sub(MAX_FOLDER_ITEMS);
+
+        let submenu = Submenu::with_id(app, format!("folder:{}", folder), folder_label(folder), true)?;
+        for prompt in items.into_iter().take(MAX_FOLDER_ITEMS) {
+            submenu.append(&prompt_menu_item(app, "folder", prompt)?)?;
+        }
+        if hidden > 0 {
+            submenu.append(&PredefinedMenuItem::separator(app)?)?;
+            submenu.append(&MenuItem::with_id(
+                app,
+                format!("{}{}", MORE_ITEM_PREFIX, folder),
+                tr("tray_more", &[&hidden]),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&submenu)?;
+        has_folders = true;
+    }
+    if has_folders {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "pause_hotkey",
+        tr("tray_pause_hotkey", &[]),
+        true,
+        hotkeys_paused(app),
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", tr("tray_quit", &[]), true, None::<&str>)?)?;
+
+    Ok(menu)
+}
+
+fn copy_prompt(app: &AppHandle, folder: &str, id: &str) -> Result<(), AppError> {
+    let data_dir = ensure_directories(app)?;
+    let file_path = data_dir.join(folder).join(format!("{}.md", id));
+    let content = read_prompt_file(&file_path)?;
+    let prompt = parse_markdown_prompt(&content, &file_path.to_string_lossy(), folder)?;
+
+    clipboard::write_text(app, &render_template(&prompt.content, &HashMap::new()))?;
+    log_usage(&data_dir, &prompt.id, folder, UsageAction::Copy);
+    let _ = app.emit("tray-prompt-copied", &prompt.id);
+    Ok(())
+}
+
+fn toggle_hotkey_pause(app: &AppHandle) {
+    let paused = !hotkeys_paused(app);
+    if let Err(e) = set_hotkeys_paused(app, paused) {
+        eprintln!("[PromptFlow] Failed to resume hotkey: {}", e);
+    }
+    if !paused {
+        refresh_prompt_hotkeys(app);
+    }
+    // 重新生成菜单，让勾选状态与实际状态一致
+    refresh_tray_menu(app);
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        "quit" => app.exit(0),
+        "show" => show_main_window(app),
+        "pause_hotkey" => toggle_hotkey_pause(app),
+        id if id.starts_with(MORE_ITEM_PREFIX) => show_main_window(app),
+        id => {
+            let target = id
+                .strip_prefix(PROMPT_ITEM_PREFIX)
+                .and_then(|rest| rest.split_once(':'))
+                .and_then(|(_, path)| path.split_once('/'));
+
+            if let Some((folder, prompt_id)) = target {
+                if let Err(e) = copy_prompt(app, folder, prompt_id) {
+                    eprintln!("[PromptFlow] Failed to copy prompt {} from tray: {}", prompt_id, e);
+                }
+            }
+        }
+    }
+}
+
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                ..
+            } = event
+            {
+                toggle_main_window(tray.app_handle());
+            }
+        });
+
+    // 使用应用默认图标作为 tray icon
+    if let Some(icon) = app.default_window_icon().cloned() {
+        builder = builder.icon(icon);
+    }
+
+    builder.build(app)?;
+    Ok(())
+}
+
+/// prompt 库变化后重建托盘菜单；读取 prompt 库较慢，在后台线程执行，不阻塞调用方
+pub fn refresh_tray_menu(app: &AppHandle) {
+    {
+        let mut state = REFRESH.lock().unwrap_or_else(|e| e.into_inner());
+        if state.running {
+            state.pending = true;
+            return;
+        }
+        state.running = true;
+    }
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        rebuild_tray_menu(&app);
+
+        let mut state = REFRESH.lock().unwrap_or_else(|e| e.into_inner());
+        if !state.pending {
+            state.running = false;
+            return;
+        }
+        state.pending = false;
+    });
+}
+
+fn rebuild_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+
+    match build_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                eprintln!("[PromptFlow] Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => eprintln!("[PromptFlow] Failed to build tray menu: {}", e),
+    }
+}
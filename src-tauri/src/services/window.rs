@@ -1 +1,266 @@
-// 此模块已被 tauri-nspanel 替代，保留为空以避免破坏模块结构
+use crate::commands::file::load_config_sync;
+use crate::models::{UIConfig, WindowGeometry, WindowState};
+use crate::utils::write_atomic;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Position, Size, WebviewWindow};
+
+#[cfg(target_os = "macos")]
+use super::panel;
+
+const MAIN_WINDOW: &str = "main";
+const WINDOW_STATE_FILE: &str = "window_state.json";
+// 拖动或缩放时事件非常密集，停止变化一段时间后再写入
+const GEOMETRY_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+// 待执行保存的截止时间；有值时已经有线程在等待
+static PENDING_GEOMETRY_SAVE: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn window_state_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let document_dir = tauri::path::BaseDirectory::Document;
+    let data_dir = app_handle.path().resolve("PromptFlow", document_dir).ok()?;
+    Some(data_dir.join(WINDOW_STATE_FILE))
+}
+
+fn load_window_state(app_handle: &AppHandle) -> WindowState {
+    window_state_path(app_handle)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 用所有显示器的位置和分辨率标识当前的显示器布局
+fn layout_key(window: &WebviewWindow) -> Option<String> {
+    let mut monitors: Vec<String> = window
+        .available_monitors()
+        .ok()?
+        .iter()
+        .map(|m| {
+            let (pos, size) = (m.position(), m.size());
+            format!("{},{},{}x{}", pos.x, pos.y, size.width, size.height)
+        })
+        .collect();
+    if monitors.is_empty() {
+        return None;
+    }
+    monitors.sort();
+    Some(monitors.join("|"))
+}
+
+fn ui_config(app_handle: &AppHandle) -> Option<UIConfig> {
+    load_config_sync(app_handle).ok().map(|c| c.ui)
+}
+
+/// 窗口移动或缩放时调用；连续的事件只会在最后一次之后保存一次
+pub fn schedule_save_window_geometry(app_handle: &AppHandle) {
+    let mut pending = PENDING_GEOMETRY_SAVE.lock().unwrap_or_else(|e| e.into_inner());
+    let waiting = pending.is_some();
+    *pending = Some(Instant::now() + GEOMETRY_SAVE_DELAY);
+    if waiting {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    thread::spawn(move || loop {
+        let mut pending = PENDING_GEOMETRY_SAVE.lock().unwrap_or_else(|e| e.into_inner());
+        let remaining = pending.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        match remaining {
+            Some(remaining) if !remaining.is_zero() => {
+                drop(pending);
+                thread::sleep(remaining);
+            }
+            _ => {
+                *pending = None;
+                drop(pending);
+                save_window_geometry(&app_handle);
+                return;
+            }
+        }
+    });
+}
+
+/// 记录主窗口当前的位置与尺寸；在隐藏时以及移动、缩放停止后调用
+pub fn save_window_geometry(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    if !ui_config(app_handle).map(|ui| ui.remember_position).unwrap_or(false) {
+        return;
+    }
+    // 最小化时 Windows 会报告 (-32000, -32000) 之类的坐标
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let (Ok(position), Ok(size), Some(key), Some(path)) = (
+        window.outer_position(),
+        window.inner_size(),
+        layout_key(&window),
+        window_state_path(app_handle),
+    ) else {
+        return;
+    };
+
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let mut state = load_window_state(app_handle);
+    if state.layouts.get(&key) == Some(&geometry) {
+        return;
+    }
+    state.layouts.insert(key, geometry);
+
+    let result = serde_json::to_string_pretty(&state)
+        .map_err(Into::into)
+        .and_then(|json| write_atomic(&path, json));
+    if let Err(e) = result {
+        eprintln!("[PromptFlow] Failed to save window state: {}", e);
+    }
+}
+
+/// 找到与给定点所在的显示器，找不到时退回主显示器
+fn monitor_at(window: &WebviewWindow, x: f64, y: f64) -> Option<Monitor> {
+    window
+        .monitor_from_point(x, y)
+        .ok()
+        .flatten()
+        .or_else(|| window.current_monitor().ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
+
+/// 把窗口限制在显示器的可用区域内（排除菜单栏、任务栏）
+fn clamp_to_monitor(monitor: &Monitor, x: i32, y: i32, size: PhysicalSize<u32>) -> PhysicalPosition<i32> {
+    let area = monitor.work_area();
+    let max_x = area.position.x + area.size.width.saturating_sub(size.width) as i32;
+    let max_y = area.position.y + area.size.height.saturating_sub(size.height) as i32;
+
+    PhysicalPosition {
+        x: x.clamp(area.position.x, max_x),
+        y: y.clamp(area.position.y, max_y),
+    }
+}
+
+fn centered_in(monitor: &Monitor, size: PhysicalSize<u32>) -> PhysicalPosition<i32> {
+    let area = monitor.work_area();
+    let x = area.position.x + (area.size.width as i32 - size.width as i32) / 2;
+    let y = area.position.y + (area.size.height as i32 - size.height as i32) / 2;
+    clamp_to_monitor(monitor, x, y, size)
+}
+
+/// 按 `window_position` 摆放主窗口：center 在鼠标所在屏幕居中，cursor 出现在鼠标下方，
+/// remembered（旧配置中为 fixed）恢复上次记录的位置
+fn place_main_window(app_handle: &AppHandle, window: &WebviewWindow) {
+    let Some(ui) = ui_config(app_handle) else {
+        return;
+    };
+
+    let remembered = if ui.remember_position {
+        layout_key(window).and_then(|key| load_window_state(app_handle).layouts.get(&key).copied())
+    } else {
+        None
+    };
+
+    if let Some(geometry) = remembered {
+        let _ = window.set_size(Size::Physical(PhysicalSize {
+            width: geometry.width,
+            height: geometry.height,
+        }));
+    }
+
+    let Ok(size) = window.outer_size() else {
+        return;
+    };
+    let cursor = window.cursor_position().ok();
+    let (cursor_x, cursor_y) = cursor.map(|c| (c.x, c.y)).unwrap_or_default();
+
+    let position = match (ui.window_position.as_str(), remembered) {
+        ("remembered" | "fixed", Some(geometry)) => {
+            let center_x = geometry.x as f64 + size.width as f64 / 2.0;
+            let center_y = geometry.y as f64 + size.height as f64 / 2.0;
+            monitor_at(window, center_x, center_y).map(|m| clamp_to_monitor(&m, geometry.x, geometry.y, size))
+        }
+        ("cursor", _) if cursor.is_some() => monitor_at(window, cursor_x, cursor_y).map(|m| {
+            let x = cursor_x as i32 - size.width as i32 / 2;
+            clamp_to_monitor(&m, x, cursor_y as i32, size)
+        }),
+        _ => monitor_at(window, cursor_x, cursor_y).map(|m| centered_in(&m, size)),
+    };
+
+    if let Some(position) = position {
+        let _ = window.set_position(Position::Physical(position));
+    }
+}
+
+pub fn is_main_window_visible(app_handle: &AppHandle) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        panel::is_panel_visible(app_handle)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        app_handle
+            .get_webview_window(MAIN_WINDOW)
+            .map(|w| w.is_visible().unwrap_or(false))
+            .unwrap_or(false)
+    }
+}
+
+/// 按配置摆放后显示主窗口
+pub fn show_main_window(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    place_main_window(app_handle, &window);
+
+    #[cfg(target_os = "macos")]
+    {
+        // NSPanel 只能在主线程操作，而命令和托盘线程都会调用这里
+        let handle = app_handle.clone();
+        if let Err(e) = app_handle.run_on_main_thread(move || panel::show_panel(&handle)) {
+            eprintln!("[PromptFlow] Failed to show main panel: {}", e);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = window.show();
+        let _ = window.set_always_on_top(true);
+        let _ = window.set_focus();
+    }
+}
+
+/// 隐藏主窗口；macOS 下主窗口是 NSPanel，需要通过 panel 在主线程隐藏
+pub fn hide_main_window(app_handle: &AppHandle) {
+    save_window_geometry(app_handle);
+
+    #[cfg(target_os = "macos")]
+    {
+        let handle = app_handle.clone();
+        if let Err(e) = app_handle.run_on_main_thread(move || panel::hide_panel(&handle)) {
+            eprintln!("[PromptFlow] Failed to hide main panel: {}", e);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        if let Some(window) = app_handle.get_webview_window(MAIN_WINDOW) {
+            let _ = window.hide();
+        }
+    }
+}
+
+pub fn toggle_main_window(app_handle: &AppHandle) {
+    if is_main_window_visible(app_handle) {
+        hide_main_window(app_handle);
+    } else {
+        show_main_window(app_handle);
+    }
+}
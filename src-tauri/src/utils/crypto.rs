@@ -0,0 +1,152 @@
+use crate::utils::AppError;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use zeroize::Zeroizing;
+
+/// 加密文件的格式：MAGIC + 24 字节 nonce + 密文（含认证标签）
+const MAGIC: &[u8] = b"PFVAULT1";
+const NONCE_LEN: usize = 24;
+
+pub const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+
+// 参数随 .vault.json 同步，可能被篡改；限制上限，避免强制分配过多内存或长时间计算
+const MAX_KDF_MEMORY_KIB: u32 = 256 * 1024;
+const MAX_KDF_ITERATIONS: u32 = 16;
+const MAX_KDF_PARALLELISM: u32 = 8;
+
+pub fn random_bytes<const N: usize>() -> Result<[u8; N], AppError> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| AppError::localized("IO_ERROR", "random_failed", &[&e]))?;
+    Ok(bytes)
+}
+
+/// 用 Argon2id 从口令派生密钥；参数随保险库保存，以后调整默认值不影响已有保险库
+pub fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, AppError> {
+    if memory_kib > MAX_KDF_MEMORY_KIB || iterations > MAX_KDF_ITERATIONS || parallelism > MAX_KDF_PARALLELISM {
+        return Err(AppError::localized("VALIDATION_ERROR", "kdf_params_invalid", &[]));
+    }
+    let params = Params::new(memory_kib, iterations, parallelism, Some(KEY_LEN))
+        .map_err(|_| AppError::localized("VALIDATION_ERROR", "kdf_params_invalid", &[]))?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| AppError::localized("CRYPTO_ERROR", "derive_key_failed", &[&e]))?;
+    Ok(key)
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn encrypt(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let ciphertext = XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(&nonce), plaintext)
+        .map_err(|_| AppError::localized("CRYPTO_ERROR", "encrypt_failed", &[]))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// 密钥错误或数据被篡改时认证失败，返回 CRYPTO_ERROR
+pub fn decrypt(key: &[u8; KEY_LEN], data: &[u8]) -> Result<Vec<u8>, AppError> {
+    if !is_encrypted(data) || data.len() < MAGIC.len() + NONCE_LEN {
+        return Err(AppError::localized("PARSE_ERROR", "not_encrypted", &[]));
+    }
+    let (nonce, ciphertext) = data[MAGIC.len()..].split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::localized("CRYPTO_ERROR", "decrypt_failed", &[]))
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试使用 Argon2 允许的最小参数，避免拖慢测试
+    fn test_key(passphrase: &str, salt: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+        derive_key(passphrase, salt, 8, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn round_trips_plaintext() {
+        let key = test_key("correct horse", b"0123456789abcdef");
+        let plaintext = "---\ntitle: \"客户\"\n---\n内部系统".as_bytes();
+        let encrypted = encrypt(&key, plaintext).unwrap();
+
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.windows(plaintext.len()).any(|w| w == plaintext));
+        assert_eq!(decrypt(&key, &encrypted).unwrap(), plaintext);
+        // 每次加密使用新的 nonce
+        assert_ne!(encrypt(&key, plaintext).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn derives_keys_deterministically() {
+        let salt = b"0123456789abcdef";
+        assert_eq!(*test_key("pass phrase", salt), *test_key("pass phrase", salt));
+        assert_ne!(*test_key("pass phrase", salt), *test_key("pass phrase", b"fedcba9876543210"));
+        assert_ne!(*test_key("pass phrase", salt), *test_key("pass phrasf", salt));
+    }
+
+    #[test]
+    fn rejects_wrong_passphrase_and_tampering() {
+        let salt = b"0123456789abcdef";
+        let encrypted = encrypt(&test_key("right passphrase", salt), b"secret").unwrap();
+
+        let err = decrypt(&test_key("wrong passphrase", salt), &encrypted).unwrap_err();
+        assert_eq!(err.code, "CRYPTO_ERROR");
+
+        let key = test_key("right passphrase", salt);
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(decrypt(&key, &tampered).unwrap_err().code, "CRYPTO_ERROR");
+        assert_eq!(decrypt(&key, &encrypted[..MAGIC.len() + 4]).unwrap_err().code, "PARSE_ERROR");
+        assert_eq!(decrypt(&key, b"plain markdown").unwrap_err().code, "PARSE_ERROR");
+    }
+
+    #[test]
+    fn rejects_out_of_range_kdf_params() {
+        let salt = b"0123456789abcdef";
+        for (memory, iterations, parallelism) in [(u32::MAX, 2, 1), (19 * 1024, 1000, 1), (19 * 1024, 2, 64), (1, 1, 1)] {
+            let err = derive_key("passphrase", salt, memory, iterations, parallelism).unwrap_err();
+            assert_eq!(err.key, Some("kdf_params_invalid"));
+        }
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        let bytes = [0u8, 1, 0xab, 0xff];
+        assert_eq!(to_hex(&bytes), "0001abff");
+        assert_eq!(from_hex("0001abff").unwrap(), bytes);
+        assert_eq!(from_hex("0001ABFF").unwrap(), bytes);
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+        assert_eq!(from_hex("é0"), None);
+    }
+}
@@ -0,0 +1,240 @@
+use super::AppError;
+use crate::models::DeepLinkAction;
+use percent_encoding::percent_decode_str;
+use std::collections::HashMap;
+use url::Url;
+
+pub const DEEP_LINK_SCHEME: &str = "promptflow";
+
+// Windows 命令行上限约 32K 字符，链接经由启动参数转发，超过这个长度没有意义
+const MAX_URL_LEN: usize = 32 * 1024;
+const MAX_ID_LEN: usize = 128;
+const MAX_TITLE_LEN: usize = 200;
+const MAX_DESCRIPTION_LEN: usize = 1000;
+const MAX_TAGS: usize = 20;
+const MAX_TAG_LEN: usize = 50;
+const MAX_VARIABLES: usize = 50;
+const MAX_VARIABLE_NAME_LEN: usize = 64;
+
+fn invalid(message: impl Into<String>) -> AppError {
+    AppError::new(message, "INVALID_DEEP_LINK")
+}
+
+fn check_len(field: &str, value: &str, max: usize) -> Result<(), AppError> {
+    if value.chars().count() > max {
+        return Err(invalid(format!("{} exceeds {} characters", field, max)));
+    }
+    Ok(())
+}
+
+/// prompt id 来自文件名，只允许 sanitize 后的字符，防止路径穿越
+fn parse_id(url: &Url) -> Result<String, AppError> {
+    let mut segments = url.path_segments().into_iter().flatten().filter(|s| !s.is_empty());
+    let raw_id = segments.next().ok_or_else(|| invalid("Missing prompt id"))?;
+    if segments.next().is_some() {
+        return Err(invalid("Unexpected path segments after prompt id"));
+    }
+
+    let id = percent_decode_str(raw_id)
+        .decode_utf8()
+        .map_err(|_| invalid("Prompt id is not valid UTF-8"))?;
+    check_len("Prompt id", &id, MAX_ID_LEN)?;
+    let valid = id
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(invalid(format!("Invalid prompt id: {}", id)));
+    }
+    Ok(id.to_string())
+}
+
+fn parse_folder(folder: Option<&String>) -> Result<Option<String>, AppError> {
+    match folder.map(|f| f.as_str()) {
+        None => Ok(None),
+        Some(folder @ ("favorites" | "templates")) => Ok(Some(folder.to_string())),
+        Some(other) => Err(invalid(format!("Unknown folder: {}", other))),
+    }
+}
+
+fn parse_tags(raw: Option<&String>) -> Result<Vec<String>, AppError> {
+    let Some(raw) = raw else {
+        return Ok(vec![]);
+    };
+
+    let mut tags: Vec<String> = vec![];
+    for tag in raw.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        check_len("Tag", tag, MAX_TAG_LEN)?;
+        if tag.contains(['"', '[', ']']) {
+            return Err(invalid(format!("Tag contains invalid characters: {}", tag)));
+        }
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    if tags.len() > MAX_TAGS {
+        return Err(invalid(format!("Too many tags (max {})", MAX_TAGS)));
+    }
+    Ok(tags)
+}
+
+/// 解析并校验 promptflow:// 链接
+pub fn parse_deep_link(raw: &str) -> Result<DeepLinkAction, AppError> {
+    if raw.len() > MAX_URL_LEN {
+        return Err(invalid(format!("Link exceeds {} bytes", MAX_URL_LEN)));
+    }
+
+    let url = Url::parse(raw).map_err(|e| invalid(format!("Malformed link: {}", e)))?;
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(invalid(format!("Unsupported scheme: {}", url.scheme())));
+    }
+
+    let mut query: HashMap<String, String> = HashMap::new();
+    for (key, value) in url.query_pairs() {
+        if query.insert(key.to_string(), value.to_string()).is_some() {
+            return Err(invalid(format!("Duplicate query parameter: {}", key)));
+        }
+    }
+
+    match url.host_str().unwrap_or_default() {
+        "open" => Ok(DeepLinkAction::Open {
+            id: parse_id(&url)?,
+            folder: parse_folder(query.get("folder"))?,
+        }),
+        "new" => {
+            let title = query.get("title").map(|t| t.trim()).unwrap_or_default();
+            if title.is_empty() {
+                return Err(invalid("Missing title"));
+            }
+            check_len("Title", title, MAX_TITLE_LEN)?;
+
+            let content = query.get("content").cloned().unwrap_or_default();
+            if content.trim().is_empty() {
+                return Err(invalid("Missing content"));
+            }
+
+            let description = query.get("description").map(|d| d.trim()).filter(|d| !d.is_empty());
+            if let Some(description) = description {
+                check_len("Description", description, MAX_DESCRIPTION_LEN)?;
+            }
+
+            Ok(DeepLinkAction::New {
+                title: title.to_string(),
+                content,
+                tags: parse_tags(query.get("tags"))?,
+                description: description.map(|d| d.to_string()),
+                folder: parse_folder(query.get("folder"))?.unwrap_or_else(|| "favorites".to_string()),
+            })
+        }
+        "render" => {
+            let id = parse_id(&url)?;
+            let folder = parse_folder(query.remove("folder").as_ref())?;
+
+            if query.len() > MAX_VARIABLES {
+                return Err(invalid(format!("Too many variables (max {})", MAX_VARIABLES)));
+            }
+            for name in query.keys() {
+                check_len("Variable name", name, MAX_VARIABLE_NAME_LEN)?;
+                if name.trim().is_empty() || name.contains(['[', ']']) {
+                    return Err(invalid(format!("Invalid variable name: {}", name)));
+                }
+            }
+
+            Ok(DeepLinkAction::Render { id, folder, variables: query })
+        }
+        "" => Err(invalid("Missing action")),
+        other => Err(invalid(format!("Unknown action: {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_code(raw: &str) -> String {
+        parse_deep_link(raw).unwrap_err().code
+    }
+
+    #[test]
+    fn parses_open_with_folder() {
+        assert_eq!(
+            parse_deep_link("promptflow://open/code-review?folder=templates").unwrap(),
+            DeepLinkAction::Open {
+                id: "code-review".to_string(),
+                folder: Some("templates".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_deep_link("promptflow://open/%E7%BF%BB%E8%AF%91").unwrap(),
+            DeepLinkAction::Open { id: "翻译".to_string(), folder: None }
+        );
+    }
+
+    #[test]
+    fn parses_new_with_defaults() {
+        let action = parse_deep_link("promptflow://new?title=%20Hello%20&content=Say+hi&tags=a,%20b,a").unwrap();
+        assert_eq!(
+            action,
+            DeepLinkAction::New {
+                title: "Hello".to_string(),
+                content: "Say hi".to_string(),
+                tags: vec!["a".to_string(), "b".to_string()],
+                description: None,
+                folder: "favorites".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_render_variables() {
+        let DeepLinkAction::Render { id, folder, variables } =
+            parse_deep_link("promptflow://render/greet?folder=favorites&name=Ann&lang=en").unwrap()
+        else {
+            panic!("expected render");
+        };
+        assert_eq!(id, "greet");
+        assert_eq!(folder.as_deref(), Some("favorites"));
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables["name"], "Ann");
+    }
+
+    #[test]
+    fn rejects_path_traversal_ids() {
+        for raw in [
+            "promptflow://open/..%2Fconfig",
+            "promptflow://open/a/b",
+            "promptflow://open/a.b",
+            "promptflow://open/",
+        ] {
+            assert_eq!(error_code(raw), "INVALID_DEEP_LINK", "{}", raw);
+        }
+    }
+
+    #[test]
+    fn only_accepts_public_folders() {
+        for raw in [
+            "promptflow://open/x?folder=vault",
+            "promptflow://open/x?folder=..",
+            "promptflow://new?title=t&content=c&folder=vault",
+        ] {
+            assert_eq!(error_code(raw), "INVALID_DEEP_LINK", "{}", raw);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        let long_title = "x".repeat(MAX_TITLE_LEN + 1);
+        for raw in [
+            "https://open/x".to_string(),
+            "promptflow://delete/x".to_string(),
+            "promptflow://new?content=c".to_string(),
+            "promptflow://new?title=t&content=%20".to_string(),
+            "promptflow://new?title=t&content=c&title=u".to_string(),
+            "promptflow://new?title=t&content=c&tags=a%22b".to_string(),
+            format!("promptflow://new?title={}&content=c", long_title),
+            format!("promptflow://open/{}", "x".repeat(MAX_URL_LEN)),
+        ] {
+            assert_eq!(error_code(&raw), "INVALID_DEEP_LINK", "{}", raw);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use serde::Serialize;
+
+// 超过该规模（token 数乘积）时不再做 LCS，直接视为整体替换，避免占用过多内存
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSegment {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+/// 按词（含空白）做 diff，拼接所有 Equal + Delete 得到原文，Equal + Insert 得到新文
+pub fn diff_words(old: &str, new: &str) -> Vec<DiffSegment> {
+    diff_tokens(&tokenize_words(old), &tokenize_words(new))
+}
+
+/// 按行做 diff，每个 token 保留行尾换行符
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffSegment> {
+    diff_tokens(&old.split_inclusive('\n').collect::<Vec<_>>(), &new.split_inclusive('\n').collect::<Vec<_>>())
+}
+
+fn tokenize_words(text: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut prev_kind: Option<u8> = None;
+
+    for (idx, c) in text.char_indices() {
+        // 0: 空白, 1: 单词字符, 2: 标点/CJK（每个字符单独成词）
+        let kind = if c.is_whitespace() {
+            0
+        } else if (c.is_alphanumeric() && !is_cjk(c)) || c == '_' {
+            1
+        } else {
+            2
+        };
+
+        if let Some(prev) = prev_kind {
+            if prev != kind || kind == 2 {
+                tokens.push(&text[start..idx]);
+                start = idx;
+            }
+        }
+        prev_kind = Some(kind);
+    }
+
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+
+    tokens
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+}
+
+fn diff_tokens(old: &[&str], new: &[&str]) -> Vec<DiffSegment> {
+    // 去掉公共前后缀，缩小 LCS 表
+    let prefix = old.iter().zip(new.iter()).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut segments = vec![];
+    push_segment(&mut segments, DiffKind::Equal, &old[..prefix]);
+
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        push_segment(&mut segments, DiffKind::Delete, old_mid);
+        push_segment(&mut segments, DiffKind::Insert, new_mid);
+    } else {
+        for (kind, token) in lcs_ops(old_mid, new_mid) {
+            push_segment(&mut segments, kind, &[token]);
+        }
+    }
+
+    push_segment(&mut segments, DiffKind::Equal, &old[old.len() - suffix..]);
+    segments
+}
+
+fn lcs_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffKind, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    let mut table = vec![0u32; (n + 1) * (m + 1)];
+    let idx = |i: usize, j: usize| i * (m + 1) + j;
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[idx(i, j)] = if old[i] == new[j] {
+                table[idx(i + 1, j + 1)] + 1
+            } else {
+                table[idx(i + 1, j)].max(table[idx(i, j + 1)])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push((DiffKind::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if table[idx(i + 1, j)] >= table[idx(i, j + 1)] {
+            ops.push((DiffKind::Delete, old[i]));
+            i += 1;
+        } else {
+            ops.push((DiffKind::Insert, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|t| (DiffKind::Delete, *t)));
+    ops.extend(new[j..].iter().map(|t| (DiffKind::Insert, *t)));
+    ops
+}
+
+fn push_segment(segments: &mut Vec<DiffSegment>, kind: DiffKind, tokens: &[&str]) {
+    if tokens.is_empty() {
+        return;
+    }
+
+    let text: String = tokens.concat();
+    match segments.last_mut() {
+        Some(last) if last.kind == kind => last.text.push_str(&text),
+        _ => segments.push(DiffSegment { kind, text }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rebuild(segments: &[DiffSegment], skip: DiffKind) -> String {
+        segments.iter().filter(|s| s.kind != skip).map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn word_diff_reconstructs_both_sides() {
+        let old = "The quick brown fox, jumps.";
+        let new = "The slow brown fox jumps over.";
+        let segments = diff_words(old, new);
+
+        assert_eq!(rebuild(&segments, DiffKind::Insert), old);
+        assert_eq!(rebuild(&segments, DiffKind::Delete), new);
+        assert!(segments.contains(&DiffSegment { kind: DiffKind::Delete, text: "quick".into() }));
+        assert!(segments.contains(&DiffSegment { kind: DiffKind::Insert, text: "slow".into() }));
+    }
+
+    #[test]
+    fn word_diff_splits_cjk_per_character() {
+        let segments = diff_words("你好世界", "你好中国");
+        assert_eq!(
+            segments,
+            vec![
+                DiffSegment { kind: DiffKind::Equal, text: "你好".into() },
+                DiffSegment { kind: DiffKind::Delete, text: "世界".into() },
+                DiffSegment { kind: DiffKind::Insert, text: "中国".into() },
+            ]
+        );
+    }
+
+    #[test]
+    fn line_diff_keeps_line_endings() {
+        let old = "a\nb\nc\n";
+        let new = "a\nB\nc\nd";
+        let segments = diff_lines(old, new);
+
+        assert_eq!(rebuild(&segments, DiffKind::Insert), old);
+        assert_eq!(rebuild(&segments, DiffKind::Delete), new);
+        assert_eq!(segments[0], DiffSegment { kind: DiffKind::Equal, text: "a\n".into() });
+        assert!(segments.contains(&DiffSegment { kind: DiffKind::Delete, text: "b\n".into() }));
+        assert!(segments.contains(&DiffSegment { kind: DiffKind::Insert, text: "B\n".into() }));
+    }
+
+    #[test]
+    fn identical_and_empty_inputs() {
+        assert_eq!(diff_lines("same\n", "same\n"), vec![DiffSegment { kind: DiffKind::Equal, text: "same\n".into() }]);
+        assert!(diff_words("", "").is_empty());
+        assert_eq!(diff_words("", "new"), vec![DiffSegment { kind: DiffKind::Insert, text: "new".into() }]);
+    }
+
+    #[test]
+    fn large_inputs_fall_back_to_whole_replacement() {
+        // 中间部分的 token 数乘积超过 MAX_LCS_CELLS，不再逐词比较
+        let old: String = (0..2100).map(|i| format!("a{}\n", i)).collect();
+        let new: String = (0..2100).map(|i| format!("b{}\n", i)).collect();
+        let segments = diff_lines(&format!("head\n{}tail\n", old), &format!("head\n{}tail\n", new));
+
+        assert_eq!(
+            segments,
+            vec![
+                DiffSegment { kind: DiffKind::Equal, text: "head\n".into() },
+                DiffSegment { kind: DiffKind::Delete, text: old },
+                DiffSegment { kind: DiffKind::Insert, text: new },
+                DiffSegment { kind: DiffKind::Equal, text: "tail\n".into() },
+            ]
+        );
+    }
+}
@@ -1,9 +1,15 @@
+use super::i18n::tr;
 use serde::Serialize;
+use std::fmt::Display;
 
 #[derive(Debug, Serialize)]
 pub struct AppError {
+    /// 按界面语言生成的文案
     pub message: String,
     pub code: String,
+    /// 具体消息的稳定标识，不随语言变化；未本地化的错误没有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<&'static str>,
 }
 
 #[allow(dead_code)]
@@ -12,9 +18,24 @@ impl AppError {
         Self {
             message: message.into(),
             code: code.into(),
+            key: None,
         }
     }
 
+    /// 用当前语言的文案构造错误，参见 `i18n::tr`
+    pub fn localized(code: impl Into<String>, key: &'static str, args: &[&dyn Display]) -> Self {
+        Self {
+            message: tr(key, args),
+            code: code.into(),
+            key: Some(key),
+        }
+    }
+
+    /// 读写某个路径失败；`key` 对应的文案依次接收路径和底层错误
+    pub fn io_failed(key: &'static str, path: impl Display, err: impl Display) -> Self {
+        Self::localized("IO_ERROR", key, &[&path, &err])
+    }
+
     pub fn io_error(message: impl Into<String>) -> Self {
         Self::new(message, "IO_ERROR")
     }
@@ -30,11 +51,19 @@ impl AppError {
     pub fn validation_error(message: impl Into<String>) -> Self {
         Self::new(message, "VALIDATION_ERROR")
     }
+
+    pub fn git_error(message: impl Into<String>) -> Self {
+        Self::new(message, "GIT_ERROR")
+    }
 }
 
+/// 命令以字符串返回错误，本地化的错误把 key 写进前缀（`[CODE:key] message`），界面据此识别具体错误
 impl std::fmt::Display for AppError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}] {}", self.code, self.message)
+        match self.key {
+            Some(key) => write!(f, "[{}:{}] {}", self.code, key, self.message),
+            None => write!(f, "[{}] {}", self.code, self.message),
+        }
     }
 }
 
@@ -0,0 +1,95 @@
+use crate::utils::AppError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// 先写临时文件再 rename，避免写到一半时留下损坏的文件
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        AppError::from(e)
+    })
+}
+
+pub fn append_json_line<T: Serialize>(path: &Path, value: &T) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut line = serde_json::to_string(value)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// 读取 JSON Lines 文件，无法解析的行会被跳过（例如写入中断留下的半行）
+pub fn read_json_lines<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, AppError> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub fn write_json_lines<T: Serialize>(path: &Path, values: &[T]) -> Result<(), AppError> {
+    let mut content = String::new();
+    for value in values {
+        content.push_str(&serde_json::to_string(value)?);
+        content.push('\n');
+    }
+    write_atomic(path, content)
+}
+
+/// 是否是单个安全的文件名：不能为空、不能是 `.`/`..`，也不能包含路径分隔符，避免拼接路径时越出目录
+pub fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', '\0'])
+}
+
+/// 移动文件；跨文件系统时 rename 会失败，此时退回到复制后删除
+pub fn move_file(from: &Path, to: &Path) -> Result<(), AppError> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_single_path_components() {
+        for name in ["a1b2c3", "my-prompt", "客户回复", "v1.2"] {
+            assert!(is_safe_file_name(name), "{}", name);
+        }
+        for name in ["", ".", "..", "../x", "a/b", "a\\b", "/etc", "a\0b"] {
+            assert!(!is_safe_file_name(name), "{:?}", name);
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 后端消息支持的语言，对应 `UIConfig.language`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    ZhCn,
+    En,
+}
+
+impl Language {
+    /// 以 zh 开头的都视为简体中文，其余一律英文
+    pub fn from_tag(tag: &str) -> Self {
+        if tag.trim().to_ascii_lowercase().starts_with("zh") {
+            Language::ZhCn
+        } else {
+            Language::En
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Language::ZhCn => "zh-CN",
+            Language::En => "en",
+        }
+    }
+}
+
+// 与 UIConfig 默认值一致
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(language: Language) {
+    let value = match language {
+        Language::ZhCn => 0,
+        Language::En => 1,
+    };
+    CURRENT_LANGUAGE.store(value, Ordering::Relaxed);
+}
+
+pub fn current_language() -> Language {
+    match CURRENT_LANGUAGE.load(Ordering::Relaxed) {
+        0 => Language::ZhCn,
+        _ => Language::En,
+    }
+}
+
+/// (key, English, 简体中文)；key 是稳定标识，文案可以随时调整，`{}` 依次替换为参数
+const MESSAGES: [(&str, &str, &str); 106] = [
+    ("prompt_not_found", "Prompt not found: {}", "找不到 prompt：{}"),
+    ("prompt_exists", "Prompt already exists: {}", "prompt 已存在：{}"),
+    ("prompt_empty", "Prompt content must not be empty", "prompt 内容不能为空"),
+    ("file_not_found", "File not found: {}", "找不到文件：{}"),
+    (
+        "path_outside_data_dir",
+        "{} is outside the prompt library and cannot be deleted",
+        "{} 不在 prompt 库中，不能删除",
+    ),
+    ("unknown_folder", "Unknown folder: {}", "未知目录：{}"),
+    ("invalid_prompt_id", "Invalid prompt id: {}", "无效的 prompt id：{}"),
+    ("duplicate_order_id", "Duplicate id in order: {}", "排序中有重复的 id：{}"),
+    ("data_dir_empty", "Resolved data directory is empty", "解析出的数据目录为空"),
+    (
+        "data_dir_is_file",
+        "Resolved data directory points to a file; it must be a directory",
+        "解析出的数据目录指向一个文件，必须是目录",
+    ),
+    ("create_dir_failed", "Failed to create directory {}: {}", "无法创建目录 {}：{}"),
+    ("resolve_dir_failed", "Failed to resolve data directory: {}", "无法解析数据目录：{}"),
+    ("read_file_failed", "Failed to read file {}: {}", "无法读取文件 {}：{}"),
+    ("write_file_failed", "Failed to write file {}: {}", "无法写入文件 {}：{}"),
+    ("delete_file_failed", "Failed to delete file {}: {}", "无法删除文件 {}：{}"),
+    ("read_dir_failed", "Failed to read directory {}: {}", "无法读取目录 {}：{}"),
+    ("read_config_failed", "Failed to read config: {}", "无法读取配置文件：{}"),
+    ("parse_config_failed", "Failed to parse config: {}", "无法解析配置文件：{}"),
+    ("serialize_config_failed", "Failed to serialize config: {}", "无法序列化配置：{}"),
+    ("write_config_failed", "Failed to write config: {}", "无法写入配置文件：{}"),
+    (
+        "import_unsupported",
+        "Only .zip archives and .md files can be imported",
+        "只能导入 .zip 压缩包或 .md 文件",
+    ),
+    ("remote_not_configured", "git.remoteUrl is not configured", "尚未配置 git.remoteUrl"),
+    (
+        "git_merge_aborted",
+        "Pull aborted: conflicts outside prompt files must be resolved manually: {}",
+        "已放弃拉取：以下非 prompt 文件存在冲突，需要手动解决：{}",
+    ),
+    (
+        "git_merge_in_progress",
+        "A merge is in progress; resolve or abort it before committing",
+        "正在合并中，请先解决冲突或放弃合并再提交",
+    ),
+    ("trash_item_not_found", "Trash item not found: {}", "回收站中找不到条目：{}"),
+    ("restore_target_exists", "A file already exists at {}", "目标位置已有文件：{}"),
+    ("revision_not_found", "Revision not found: {}", "找不到修订记录：{}"),
+    ("polish_run_not_found", "Polish run not found: {}", "找不到润色记录：{}"),
+    ("merge_nothing", "No duplicates to merge", "没有需要合并的重复项"),
+    ("merge_keep_conflict", "The kept prompt cannot also be merged away", "保留的 prompt 不能同时被合并掉"),
+    ("merge_duplicate_ref", "Prompt listed more than once: {}", "prompt 被重复列出：{}"),
+    ("invalid_threshold", "Threshold must be in (0, 1]", "阈值必须在 (0, 1] 之间"),
+    ("invalid_cursor", "Invalid cursor", "无效的分页游标"),
+    (
+        "relevance_needs_search",
+        "Sorting by relevance requires a search query",
+        "按相关度排序需要提供搜索词",
+    ),
+    ("tag_empty", "Tag must not be empty", "标签不能为空"),
+    ("tag_invalid", "Tag contains invalid characters: {}", "标签包含无效字符：{}"),
+    ("unknown_bucket", "Unknown bucket: {}", "未知的统计粒度：{}"),
+    ("pack_not_found", "Template pack not found: {}", "找不到模板包：{}"),
+    ("pack_not_installed", "Template pack not installed: {}", "模板包尚未安装：{}"),
+    ("pack_installed", "Template pack already installed: {}", "模板包已安装：{}"),
+    ("pack_item_missing", "Pack {} has no item {}", "模板包 {} 中没有条目 {}"),
+    ("pack_language_missing", "Pack {} has no {} variant", "模板包 {} 没有 {} 版本"),
+    ("server_token_empty", "Server token must not be empty", "服务令牌不能为空"),
+    ("request_too_large", "Request body is too large", "请求体过大"),
+    ("repository_not_found", "Repository not found: {}", "找不到仓库：{}"),
+    ("repository_exists", "Repository already registered: {}", "仓库已登记：{}"),
+    ("repository_active", "The active repository cannot be removed", "不能移除当前使用的仓库"),
+    ("repository_read_only", "Repository is read-only: {}", "仓库为只读：{}"),
+    (
+        "repository_not_active",
+        "{} is not the current repository; switch to it before editing",
+        "{} 不是当前仓库，请先切换到该仓库再编辑",
+    ),
+    ("repository_unavailable", "Repository directory does not exist: {}", "仓库目录不存在：{}"),
+    (
+        "prompt_read_only",
+        "{} belongs to the read-only repository {}; fork it to edit",
+        "{} 属于只读仓库 {}，请复制到个人仓库后再编辑",
+    ),
+    ("vault_locked", "The vault is locked", "保险库已锁定"),
+    ("vault_exists", "A vault already exists in this library", "当前库已有保险库"),
+    ("vault_not_initialized", "No vault has been created in this library", "当前库尚未创建保险库"),
+    ("vault_wrong_passphrase", "Incorrect vault passphrase", "保险库口令错误"),
+    (
+        "kdf_params_invalid",
+        "Vault key derivation parameters are out of range",
+        "保险库的密钥派生参数超出允许范围",
+    ),
+    (
+        "merge_vault_crossing",
+        "Vault prompts can only be merged with other vault prompts",
+        "保险库中的 prompt 只能与保险库中的 prompt 合并",
+    ),
+    (
+        "vault_passphrase_short",
+        "Vault passphrase must be at least {} characters",
+        "保险库口令至少需要 {} 个字符",
+    ),
+    ("documents_dir_missing", "Failed to resolve the documents directory", "无法定位文档目录"),
+    (
+        "storage_path_relative",
+        "storage.path must be an absolute path or empty (to use the default data directory)",
+        "storage.path 必须是绝对路径，留空则使用默认数据目录",
+    ),
+    ("storage_path_is_file", "storage.path points to a file; it must be a directory", "storage.path 指向一个文件，必须是目录"),
+    ("invalid_utf8", "Invalid UTF-8 in {}: {}", "{} 不是有效的 UTF-8：{}"),
+    ("export_failed", "Failed to write export archive {}: {}", "无法写入导出压缩包 {}：{}"),
+    ("zip_invalid", "Invalid ZIP archive: {}", "无效的 ZIP 压缩包：{}"),
+    ("zip_entry_invalid", "Invalid ZIP entry: {}", "无效的 ZIP 条目：{}"),
+    ("git_run_failed", "Failed to run git: {}", "无法运行 git：{}"),
+    ("git_not_initialized", "Prompt repository is not a git repository", "prompt 库还不是 git 仓库"),
+    ("hotkey_empty_segment", "Invalid hotkey \"{}\": empty key segment", "无效的快捷键“{}”：存在空的按键段"),
+    ("hotkey_modifier_order", "Invalid hotkey \"{}\": modifiers must come before the key", "无效的快捷键“{}”：修饰键必须写在按键之前"),
+    ("hotkey_multiple_keys", "Invalid hotkey \"{}\": only one non-modifier key is allowed", "无效的快捷键“{}”：只能包含一个非修饰键"),
+    ("hotkey_unknown_key", "Invalid hotkey \"{}\": unknown key \"{}\"", "无效的快捷键“{}”：未知按键“{}”"),
+    ("hotkey_missing_key", "Invalid hotkey \"{}\": missing key", "无效的快捷键“{}”：缺少按键"),
+    ("hotkey_missing_modifier", "Invalid hotkey \"{}\": at least one modifier is required", "无效的快捷键“{}”：至少需要一个修饰键"),
+    ("hotkey_register_failed", "Failed to register hotkey: {}", "无法注册快捷键：{}"),
+    ("hotkey_state_poisoned", "Hotkey state is poisoned", "快捷键状态已损坏"),
+    ("hotkey_assigned", "Hotkey {} is already assigned to \"{}\"", "快捷键 {} 已分配给“{}”"),
+    ("hotkey_main_window", "Hotkey {} is already used to toggle the main window", "快捷键 {} 已用于显示或隐藏主窗口"),
+    ("hotkey_in_use", "Hotkey {} is already in use", "快捷键 {} 已被占用"),
+    ("clipboard_read_failed", "Failed to read clipboard: {}", "无法读取剪贴板：{}"),
+    ("clipboard_write_failed", "Failed to write clipboard: {}", "无法写入剪贴板：{}"),
+    ("paste_failed", "Failed to simulate paste: {}", "无法模拟粘贴：{}"),
+    ("pack_id_invalid", "Invalid pack id: {}", "无效的模板包 id：{}"),
+    ("pack_item_invalid", "Invalid item id in pack {}: {}", "模板包 {} 中的条目 id 无效：{}"),
+    ("pack_language_invalid", "Invalid language in pack {}: {}", "模板包 {} 中的语言无效：{}"),
+    ("token_generate_failed", "Failed to generate token: {}", "无法生成令牌：{}"),
+    ("server_not_initialized", "Server state is not initialized", "服务状态尚未初始化"),
+    ("server_state_poisoned", "Server state is poisoned", "服务状态已损坏"),
+    ("server_listen_failed", "Failed to listen on {}: {}", "无法监听 {}：{}"),
+    ("host_not_allowed", "Host not allowed: {}", "不允许的 Host：{}"),
+    ("token_invalid", "Missing or invalid token", "缺少令牌或令牌无效"),
+    ("route_not_found", "No route for {} {}", "没有对应的接口：{} {}"),
+    ("random_failed", "Failed to generate random bytes: {}", "无法生成随机数：{}"),
+    ("derive_key_failed", "Failed to derive key: {}", "无法派生密钥：{}"),
+    ("encrypt_failed", "Failed to encrypt data", "加密失败"),
+    ("decrypt_failed", "Failed to decrypt data; the passphrase is wrong or the file is damaged", "解密失败：口令错误或文件已损坏"),
+    ("not_encrypted", "Not an encrypted vault file", "不是加密的保险库文件"),
+    ("vault_meta_invalid", "Vault metadata file {} is invalid", "保险库元数据文件 {} 无效"),
+    ("tray_show", "Show Window", "显示窗口"),
+    ("tray_most_used", "Most Used", "最常使用"),
+    ("tray_recent", "Recent", "最近使用"),
+    ("tray_favorites", "Favorites", "收藏"),
+    ("tray_templates", "Templates", "模板"),
+    ("tray_vault", "Vault", "保险库"),
+    ("tray_more", "{} more…", "还有 {} 个…"),
+    ("tray_pause_hotkey", "Pause Hotkey", "暂停快捷键"),
+    ("tray_quit", "Quit PromptFlow", "退出 PromptFlow"),
+];
+
+fn lookup(key: &str, language: Language) -> Option<&'static str> {
+    MESSAGES.iter().find(|(k, _, _)| *k == key).map(|(_, en, zh)| match language {
+        Language::ZhCn => *zh,
+        Language::En => *en,
+    })
+}
+
+/// 按当前语言取文案；未登记的 key 原样返回
+pub fn tr(key: &str, args: &[&dyn Display]) -> String {
+    let template = lookup(key, current_language()).unwrap_or(key);
+    let mut args = args.iter();
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn messages_have_unique_keys_and_matching_placeholders() {
+        let mut keys = HashSet::new();
+        for (key, en, zh) in MESSAGES {
+            assert!(keys.insert(key), "duplicate key {}", key);
+            assert_eq!(en.matches("{}").count(), zh.matches("{}").count(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn localized_errors_carry_their_key() {
+        let err = crate::utils::AppError::localized("NOT_FOUND", "trash_item_not_found", &[&"abc"]);
+        assert_eq!(err.key, Some("trash_item_not_found"));
+        assert!(err.to_string().starts_with("[NOT_FOUND:trash_item_not_found] "));
+        assert!(err.to_string().ends_with("abc"));
+        assert_eq!(crate::utils::AppError::not_found("x").to_string(), "[NOT_FOUND] x");
+    }
+}
@@ -0,0 +1,617 @@
+use crate::models::{LintIssue, LintSeverity, PromptItem};
+use crate::utils::{
+    generate_timestamp, parse_markdown_prompt, serialize_markdown_prompt, FRONTMATTER_DELIMITER, FRONTMATTER_KEYS,
+    PLACEHOLDER_CLOSE, PLACEHOLDER_OPEN,
+};
+use std::path::Path;
+
+/// 单个文件的检查结果；`fixed_content` 为安全修复后的完整文件内容
+pub struct FileLint {
+    pub issues: Vec<LintIssue>,
+    pub fixed_content: Option<String>,
+}
+
+struct Collector<'a> {
+    file_path: &'a str,
+    issues: Vec<LintIssue>,
+}
+
+impl Collector<'_> {
+    fn push(
+        &mut self,
+        line: Option<usize>,
+        code: &str,
+        severity: LintSeverity,
+        message: impl Into<String>,
+        suggestion: impl Into<String>,
+        fixable: bool,
+    ) {
+        self.issues.push(LintIssue {
+            file_path: self.file_path.to_string(),
+            line,
+            code: code.to_string(),
+            severity,
+            message: message.into(),
+            suggestion: suggestion.into(),
+            fixable,
+        });
+    }
+}
+
+// 需要修改的字段；只有 frontmatter 能被完整重写时才会应用
+#[derive(Default)]
+struct Fixes {
+    add_frontmatter: bool,
+    convert_encoding: bool,
+    title: bool,
+    created_at: bool,
+    updated_at: bool,
+    last_used: bool,
+    use_count: bool,
+}
+
+impl Fixes {
+    fn any(&self) -> bool {
+        self.add_frontmatter
+            || self.convert_encoding
+            || self.title
+            || self.created_at
+            || self.updated_at
+            || self.last_used
+            || self.use_count
+    }
+}
+
+/// 检查一个 prompt 文件的原始字节
+pub fn lint_prompt_file(bytes: &[u8], file_path: &str, folder: &str) -> FileLint {
+    let mut out = Collector {
+        file_path,
+        issues: vec![],
+    };
+    let mut fixes = Fixes::default();
+    // frontmatter 中有解析器不认识的内容时，重写文件会丢失这些内容
+    let mut rewrite_safe = true;
+
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(e) => match decode_utf16(bytes) {
+            Some(text) => {
+                fixes.convert_encoding = true;
+                out.push(
+                    None,
+                    "non_utf8",
+                    LintSeverity::Error,
+                    "File is UTF-16 encoded and cannot be loaded",
+                    "Convert the file to UTF-8",
+                    true,
+                );
+                text
+            }
+            None => {
+                let line = bytes[..e.valid_up_to()].iter().filter(|b| **b == b'\n').count() + 1;
+                out.push(
+                    Some(line),
+                    "non_utf8",
+                    LintSeverity::Error,
+                    "File contains invalid UTF-8 and cannot be loaded",
+                    "Re-save the file with UTF-8 encoding",
+                    false,
+                );
+                return FileLint {
+                    issues: out.issues,
+                    fixed_content: None,
+                };
+            }
+        },
+    };
+    let text = text.trim_start_matches('\u{feff}');
+    let lines: Vec<&str> = text.lines().collect();
+    let open = lines.iter().take_while(|l| l.trim().is_empty()).count();
+
+    let body_start_line;
+    let body: String;
+
+    if lines.get(open).map(|l| l.trim() != FRONTMATTER_DELIMITER).unwrap_or(true) {
+        fixes.add_frontmatter = true;
+        out.push(
+            Some(1),
+            "missing_frontmatter",
+            LintSeverity::Warning,
+            "File has no frontmatter; title and timestamps are derived on every load",
+            "Add a frontmatter block with title, created_at and updated_at",
+            true,
+        );
+        // 解析器按前两个 `---` 切分，第一个之前的内容会丢失
+        if text.matches(FRONTMATTER_DELIMITER).count() >= 2 {
+            out.push(
+                Some(1),
+                "ambiguous_delimiter",
+                LintSeverity::Error,
+                "Body contains `---` without a leading frontmatter block and will be parsed incorrectly",
+                "Add a frontmatter block at the top of the file",
+                true,
+            );
+        }
+        body_start_line = 1;
+        body = text.to_string();
+    } else {
+        let close = lines
+            .iter()
+            .skip(open + 1)
+            .position(|l| l.trim() == FRONTMATTER_DELIMITER)
+            .map(|i| i + open + 1);
+        let Some(close) = close else {
+            out.push(
+                Some(open + 1),
+                "unterminated_frontmatter",
+                LintSeverity::Error,
+                "Frontmatter is not closed; the whole file is treated as the prompt body",
+                "Add a `---` line after the frontmatter fields",
+                false,
+            );
+            return FileLint {
+                issues: out.issues,
+                fixed_content: None,
+            };
+        };
+
+        lint_frontmatter(&lines[open + 1..close], open + 2, &mut out, &mut fixes, &mut rewrite_safe);
+        body_start_line = close + 2;
+        body = lines[close + 1..].join("\n");
+    }
+
+    if body.trim().is_empty() {
+        out.push(
+            None,
+            "empty_body",
+            LintSeverity::Warning,
+            "Prompt body is empty",
+            "Add prompt content or delete the file",
+            false,
+        );
+    }
+    lint_placeholders(&body, body_start_line, &mut out);
+
+    let mut issues = out.issues;
+    let fixed_content = if rewrite_safe && fixes.any() {
+        Some(apply_fixes(text, file_path, folder, &fixes))
+    } else if fixes.convert_encoding {
+        // 无法安全重写 frontmatter 时，仍然可以只转换编码
+        Some(text.to_string())
+    } else {
+        None
+    };
+    if !rewrite_safe {
+        for issue in issues.iter_mut().filter(|i| i.code != "non_utf8") {
+            issue.fixable = false;
+        }
+    }
+
+    FileLint { issues, fixed_content }
+}
+
+fn lint_frontmatter(
+    lines: &[&str],
+    first_line: usize,
+    out: &mut Collector,
+    fixes: &mut Fixes,
+    rewrite_safe: &mut bool,
+) {
+    let mut seen: Vec<&str> = vec![];
+    let header_line = Some(first_line - 1);
+
+    for (idx, raw) in lines.iter().enumerate() {
+        let line_no = Some(first_line + idx);
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('#') {
+            *rewrite_safe = false;
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            *rewrite_safe = false;
+            out.push(
+                line_no,
+                "invalid_frontmatter_line",
+                LintSeverity::Warning,
+                format!("Frontmatter line is not a `key: value` pair: {}", line),
+                "Fix or remove the line",
+                false,
+            );
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        if !FRONTMATTER_KEYS.contains(&key) {
+            *rewrite_safe = false;
+            out.push(
+                line_no,
+                "unknown_frontmatter_key",
+                LintSeverity::Info,
+                format!("Unknown frontmatter key `{}` is ignored", key),
+                "Remove the key or check its spelling",
+                false,
+            );
+            continue;
+        }
+        if seen.contains(&key) {
+            *rewrite_safe = false;
+            out.push(
+                line_no,
+                "duplicate_frontmatter_key",
+                LintSeverity::Warning,
+                format!("Frontmatter key `{}` appears more than once; the last value wins", key),
+                "Keep only one value",
+                false,
+            );
+        }
+        seen.push(key);
+
+        match key {
+            "title" if value.trim_matches('"').trim().is_empty() => {
+                fixes.title = true;
+                out.push(
+                    line_no,
+                    "missing_title",
+                    LintSeverity::Warning,
+                    "Title is empty; the file name is shown instead",
+                    "Set a title (auto-fix uses the file name)",
+                    true,
+                );
+            }
+            "tags" if !(value.starts_with('[') && value.ends_with(']')) => {
+                *rewrite_safe = false;
+                out.push(
+                    line_no,
+                    "invalid_tags",
+                    LintSeverity::Warning,
+                    "Tags must be written as a list like [\"a\", \"b\"]; they are currently ignored",
+                    "Rewrite the tags as an inline list",
+                    false,
+                );
+            }
+            "use_count" if value.parse::<u32>().is_err() => {
+                fixes.use_count = true;
+                out.push(
+                    line_no,
+                    "invalid_use_count",
+                    LintSeverity::Warning,
+                    format!("use_count is not a number: {}", value),
+                    "Reset use_count to 0",
+                    true,
+                );
+            }
+            "created_at" | "updated_at" | "last_used" if !is_valid_timestamp(value) => {
+                match key {
+                    "created_at" => fixes.created_at = true,
+                    "updated_at" => fixes.updated_at = true,
+                    _ => fixes.last_used = true,
+                }
+                let suggestion = if key == "last_used" {
+                    "Remove last_used"
+                } else {
+                    "Replace with the current time"
+                };
+                out.push(
+                    line_no,
+                    "invalid_timestamp",
+                    LintSeverity::Warning,
+                    format!("{} is not an ISO 8601 timestamp: {}", key, value),
+                    suggestion,
+                    true,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if !seen.contains(&"title") {
+        fixes.title = true;
+        out.push(
+            header_line,
+            "missing_title",
+            LintSeverity::Warning,
+            "Frontmatter has no title; the file name is shown instead",
+            "Set a title (auto-fix uses the file name)",
+            true,
+        );
+    }
+    for key in ["created_at", "updated_at"] {
+        if !seen.contains(&key) {
+            if key == "created_at" {
+                fixes.created_at = true;
+            } else {
+                fixes.updated_at = true;
+            }
+            out.push(
+                header_line,
+                "invalid_timestamp",
+                LintSeverity::Warning,
+                format!("Frontmatter has no {}; it changes on every load", key),
+                "Set it to the current time",
+                true,
+            );
+        }
+    }
+}
+
+fn lint_placeholders(body: &str, start_line: usize, out: &mut Collector) {
+    let line_at = |offset: usize| start_line + body[..offset].matches('\n').count();
+    let mut pos = 0;
+
+    while let Some(found) = body[pos..].find(PLACEHOLDER_OPEN) {
+        let start = pos + found;
+        let inner_start = start + PLACEHOLDER_OPEN.len();
+
+        let Some(len) = body[inner_start..].find(PLACEHOLDER_CLOSE) else {
+            out.push(
+                Some(line_at(start)),
+                "placeholder_syntax",
+                LintSeverity::Warning,
+                "Placeholder `[[` is never closed",
+                "Close the placeholder with `]]` or remove it",
+                false,
+            );
+            return;
+        };
+
+        let name = &body[inner_start..inner_start + len];
+        let problem = if name.trim().is_empty() {
+            Some("Placeholder has no name")
+        } else if name.contains(PLACEHOLDER_OPEN) {
+            Some("Placeholder contains a nested `[[`")
+        } else if name.contains('\n') {
+            Some("Placeholder spans multiple lines")
+        } else {
+            None
+        };
+        if let Some(message) = problem {
+            out.push(
+                Some(line_at(start)),
+                "placeholder_syntax",
+                LintSeverity::Warning,
+                message,
+                "Use the form [[variable name]] on a single line",
+                false,
+            );
+        }
+
+        pos = inner_start + len + PLACEHOLDER_CLOSE.len();
+    }
+}
+
+/// 接受 `YYYY-MM-DDTHH:MM:SS`，可带小数秒，以 `Z` 或 `±HH:MM` 结尾
+fn is_valid_timestamp(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() < 20 || !value.is_ascii() {
+        return false;
+    }
+
+    let digits = |range: std::ops::Range<usize>| -> Option<u32> {
+        let part = &value[range];
+        part.bytes().all(|b| b.is_ascii_digit()).then(|| part.parse().ok())?
+    };
+    let separators_ok = bytes[4] == b'-' && bytes[7] == b'-' && bytes[10] == b'T' && bytes[13] == b':' && bytes[16] == b':';
+    let fields = (digits(0..4), digits(5..7), digits(8..10), digits(11..13), digits(14..16), digits(17..19));
+    let (Some(_), Some(month), Some(day), Some(hour), Some(minute), Some(second)) = fields else {
+        return false;
+    };
+    if !separators_ok || !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+
+    let mut rest = &value[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return false;
+        }
+        rest = &fraction[len..];
+    }
+
+    match rest.as_bytes() {
+        [b'Z'] => true,
+        [b'+' | b'-', h1, h2, b':', m1, m2] => [h1, h2, m1, m2].iter().all(|b| b.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+fn decode_utf16(bytes: &[u8]) -> Option<String> {
+    let (rest, little_endian) = match bytes {
+        [0xFF, 0xFE, rest @ ..] => (rest, true),
+        [0xFE, 0xFF, rest @ ..] => (rest, false),
+        _ => return None,
+    };
+    if rest.len() % 2 != 0 {
+        return None;
+    }
+
+    let units: Vec<u16> = rest
+        .chunks_exact(2)
+        .map(|c| if little_endian { u16::from_le_bytes([c[0], c[1]]) } else { u16::from_be_bytes([c[0], c[1]]) })
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+fn apply_fixes(text: &str, file_path: &str, folder: &str, fixes: &Fixes) -> String {
+    let stem = Path::new(file_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("untitled")
+        .to_string();
+    let now = generate_timestamp();
+
+    let mut prompt = if fixes.add_frontmatter {
+        // 正文可能包含 `---`，不能交给解析器按分隔符切分
+        PromptItem {
+            id: stem.clone(),
+            title: stem.clone(),
+            content: text.trim().to_string(),
+            tags: vec![],
+            description: None,
+            use_count: 0,
+            last_used: None,
+            created_at: now.clone(),
+            updated_at: now.clone(),
+            file_path: file_path.to_string(),
+            folder: folder.to_string(),
+            hotkey: None,
+            pinned: false,
+            sort_order: None,
+            repository: None,
+            read_only: false,
+            forked_from: None,
+        }
+    } else {
+        match parse_markdown_prompt(text, file_path, folder) {
+            Ok(prompt) => prompt,
+            Err(_) => return text.to_string(),
+        }
+    };
+
+    // 解析器已把空标题替换为文件名、无效 use_count 置为 0
+    if fixes.title {
+        prompt.title = stem;
+    }
+    if fixes.created_at {
+        prompt.created_at = now.clone();
+    }
+    if fixes.updated_at {
+        prompt.updated_at = now;
+    }
+    if fixes.last_used {
+        prompt.last_used = None;
+    }
+
+    serialize_markdown_prompt(&prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLEAN: &str = "---\ntitle: \"Greeting\"\ntags: [\"a\"]\ncreated_at: 2024-01-01T00:00:00Z\n\
+        updated_at: 2024-01-02T00:00:00.123+08:00\n---\n\nHello [[name]]\n";
+
+    fn codes(lint: &FileLint) -> Vec<&str> {
+        lint.issues.iter().map(|i| i.code.as_str()).collect()
+    }
+
+    #[test]
+    fn clean_file_has_no_issues() {
+        let lint = lint_prompt_file(CLEAN.as_bytes(), "greeting.md", "favorites");
+        assert!(lint.issues.is_empty());
+        assert!(lint.fixed_content.is_none());
+    }
+
+    #[test]
+    fn validates_timestamps() {
+        for valid in ["2024-01-01T00:00:00Z", "2024-12-31T23:59:60.5Z", "2024-01-01T08:00:00-05:30"] {
+            assert!(is_valid_timestamp(valid), "{}", valid);
+        }
+        for invalid in [
+            "2024-01-01",
+            "2024-01-01 00:00:00Z",
+            "2024-13-01T00:00:00Z",
+            "2024-01-01T24:00:00Z",
+            "2024-01-01T00:00:00.Z",
+            "2024-01-01T00:00:00+0800",
+            "２024-01-01T00:00:00Z",
+        ] {
+            assert!(!is_valid_timestamp(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn decodes_utf16_with_bom() {
+        let little: Vec<u8> = [0xFF, 0xFE].into_iter().chain("你好".encode_utf16().flat_map(u16::to_le_bytes)).collect();
+        let big: Vec<u8> = [0xFE, 0xFF].into_iter().chain("你好".encode_utf16().flat_map(u16::to_be_bytes)).collect();
+        assert_eq!(decode_utf16(&little).as_deref(), Some("你好"));
+        assert_eq!(decode_utf16(&big).as_deref(), Some("你好"));
+        assert_eq!(decode_utf16(&[0xFF, 0xFE, 0x41]), None);
+        assert_eq!(decode_utf16(b"plain"), None);
+    }
+
+    #[test]
+    fn converts_utf16_files_to_utf8() {
+        let bytes: Vec<u8> =
+            [0xFF, 0xFE].into_iter().chain(CLEAN.encode_utf16().flat_map(u16::to_le_bytes)).collect();
+        let lint = lint_prompt_file(&bytes, "greeting.md", "favorites");
+        assert_eq!(codes(&lint), ["non_utf8"]);
+        assert!(lint.issues[0].fixable);
+
+        let fixed = lint.fixed_content.unwrap();
+        let prompt = parse_markdown_prompt(&fixed, "greeting.md", "favorites").unwrap();
+        assert_eq!(prompt.title, "Greeting");
+        assert_eq!(prompt.content.trim(), "Hello [[name]]");
+    }
+
+    #[test]
+    fn reports_line_of_invalid_utf8() {
+        let lint = lint_prompt_file(b"---\ntitle: a\n\xFF\n", "a.md", "favorites");
+        assert_eq!(codes(&lint), ["non_utf8"]);
+        assert_eq!(lint.issues[0].line, Some(3));
+        assert!(lint.fixed_content.is_none());
+    }
+
+    #[test]
+    fn adding_frontmatter_keeps_body_with_delimiters() {
+        let body = "Intro\n---\nSection two\n---\nEnd";
+        let lint = lint_prompt_file(body.as_bytes(), "notes.md", "templates");
+        assert_eq!(codes(&lint), ["missing_frontmatter", "ambiguous_delimiter"]);
+
+        let fixed = lint.fixed_content.unwrap();
+        assert!(fixed.starts_with("---\n"));
+        assert!(fixed.ends_with(body));
+        assert!(fixed.contains("title: \"notes\""));
+    }
+
+    #[test]
+    fn fixes_fields_and_keeps_content() {
+        let text = "---\ntitle: \"\"\nuse_count: many\ncreated_at: yesterday\nupdated_at: 2024-01-02T00:00:00Z\n\
+            last_used: never\n---\n\nLine one\n\nLine [[two]]\n";
+        let lint = lint_prompt_file(text.as_bytes(), "draft.md", "favorites");
+        assert_eq!(
+            codes(&lint),
+            ["missing_title", "invalid_use_count", "invalid_timestamp", "invalid_timestamp"]
+        );
+        assert!(lint.issues.iter().all(|i| i.fixable));
+
+        let fixed = lint.fixed_content.unwrap();
+        let prompt = parse_markdown_prompt(&fixed, "draft.md", "favorites").unwrap();
+        assert_eq!(prompt.title, "draft");
+        assert_eq!(prompt.use_count, 0);
+        assert_eq!(prompt.last_used, None);
+        assert!(is_valid_timestamp(&prompt.created_at));
+        assert_eq!(prompt.updated_at, "2024-01-02T00:00:00Z");
+        assert_eq!(prompt.content.trim(), "Line one\n\nLine [[two]]");
+    }
+
+    #[test]
+    fn unknown_keys_block_rewrites() {
+        let text = "---\ntitle: \"\"\nauthor: me\ncreated_at: 2024-01-01T00:00:00Z\n\
+            updated_at: 2024-01-01T00:00:00Z\n---\nBody";
+        let lint = lint_prompt_file(text.as_bytes(), "a.md", "favorites");
+        assert_eq!(codes(&lint), ["missing_title", "unknown_frontmatter_key"]);
+        assert!(lint.issues.iter().all(|i| !i.fixable));
+        assert!(lint.fixed_content.is_none());
+    }
+
+    #[test]
+    fn reports_structural_problems() {
+        let lint = lint_prompt_file(b"---\ntitle: a\nbody without end", "a.md", "favorites");
+        assert_eq!(codes(&lint), ["unterminated_frontmatter"]);
+        assert_eq!(lint.issues[0].line, Some(1));
+
+        let text = "---\ntitle: a\ncreated_at: 2024-01-01T00:00:00Z\nupdated_at: 2024-01-01T00:00:00Z\n---\n\
+            ok [[name]]\n[[ ]] and [[a [[b]]\n[[open";
+        let lint = lint_prompt_file(text.as_bytes(), "a.md", "favorites");
+        let lines: Vec<Option<usize>> = lint.issues.iter().map(|i| i.line).collect();
+        assert_eq!(codes(&lint), ["placeholder_syntax"; 3]);
+        assert_eq!(lines, [Some(7), Some(7), Some(8)]);
+    }
+}
@@ -1,7 +1,22 @@
 use crate::models::{PromptFrontmatter, PromptItem};
 use crate::utils::AppError;
 
-const FRONTMATTER_DELIMITER: &str = "---";
+pub(crate) const FRONTMATTER_DELIMITER: &str = "---";
+
+/// 解析器识别的 frontmatter 字段，其他字段在重新保存时会被丢弃
+pub const FRONTMATTER_KEYS: [&str; 11] = [
+    "title",
+    "tags",
+    "description",
+    "use_count",
+    "last_used",
+    "created_at",
+    "updated_at",
+    "hotkey",
+    "pinned",
+    "sort_order",
+    "forked_from",
+];
 
 pub fn parse_markdown_prompt(content: &str, file_path: &str, folder: &str) -> Result<PromptItem, AppError> {
     let path = std::path::Path::new(file_path);
@@ -34,6 +49,12 @@ pub fn parse_markdown_prompt(content: &str, file_path: &str, folder: &str) -> Re
             updated_at: chrono_now(),
             file_path: file_path.to_string(),
             folder: folder.to_string(),
+            hotkey: None,
+            pinned: false,
+            sort_order: None,
+            repository: None,
+            read_only: false,
+            forked_from: None,
         });
     }
 
@@ -58,11 +79,17 @@ pub fn parse_markdown_prompt(content: &str, file_path: &str, folder: &str) -> Re
         updated_at: frontmatter.updated_at,
         file_path: file_path.to_string(),
         folder: folder.to_string(),
+        hotkey: frontmatter.hotkey,
+        pinned: frontmatter.pinned,
+        sort_order: frontmatter.sort_order,
+        repository: None,
+        read_only: false,
+        forked_from: frontmatter.forked_from,
     })
 }
 
-pub fn serialize_markdown_prompt(item: &PromptItem) -> String {
-    let frontmatter = PromptFrontmatter {
+pub fn prompt_frontmatter(item: &PromptItem) -> PromptFrontmatter {
+    PromptFrontmatter {
         title: item.title.clone(),
         tags: item.tags.clone(),
         description: item.description.clone(),
@@ -70,7 +97,15 @@ pub fn serialize_markdown_prompt(item: &PromptItem) -> String {
         last_used: item.last_used.clone(),
         created_at: item.created_at.clone(),
         updated_at: item.updated_at.clone(),
-    };
+        hotkey: item.hotkey.clone(),
+        pinned: item.pinned,
+        sort_order: item.sort_order,
+        forked_from: item.forked_from.clone(),
+    }
+}
+
+pub fn serialize_markdown_prompt(item: &PromptItem) -> String {
+    let frontmatter = prompt_frontmatter(item);
 
     let yaml = serde_yaml_serialize(&frontmatter);
     
@@ -85,6 +120,10 @@ fn serde_yaml_parse(yaml_str: &str) -> Result<PromptFrontmatter, AppError> {
     let mut last_used: Option<String> = None;
     let mut created_at = chrono_now();
     let mut updated_at = chrono_now();
+    let mut hotkey: Option<String> = None;
+    let mut pinned = false;
+    let mut sort_order: Option<u32> = None;
+    let mut forked_from: Option<String> = None;
 
     for line in yaml_str.lines() {
         let line = line.trim();
@@ -103,6 +142,15 @@ fn serde_yaml_parse(yaml_str: &str) -> Result<PromptFrontmatter, AppError> {
                 "last_used" => last_used = Some(value.to_string()),
                 "created_at" => created_at = value.to_string(),
                 "updated_at" => updated_at = value.to_string(),
+                "hotkey" => {
+                    let value = value.trim_matches('"');
+                    if !value.is_empty() {
+                        hotkey = Some(value.to_string());
+                    }
+                }
+                "pinned" => pinned = value == "true",
+                "sort_order" => sort_order = value.parse().ok(),
+                "forked_from" => forked_from = Some(value.trim_matches('"').to_string()),
                 "tags" => {
                     if value.starts_with('[') && value.ends_with(']') {
                         let inner = &value[1..value.len()-1];
@@ -126,6 +174,10 @@ fn serde_yaml_parse(yaml_str: &str) -> Result<PromptFrontmatter, AppError> {
         last_used,
         created_at,
         updated_at,
+        hotkey,
+        pinned,
+        sort_order,
+        forked_from,
     })
 }
 
@@ -157,6 +209,22 @@ fn serde_yaml_serialize(frontmatter: &PromptFrontmatter) -> String {
     
     lines.push(format!("created_at: {}", frontmatter.created_at));
     lines.push(format!("updated_at: {}", frontmatter.updated_at));
+
+    if let Some(ref hotkey) = frontmatter.hotkey {
+        lines.push(format!("hotkey: \"{}\"", hotkey));
+    }
+
+    if frontmatter.pinned {
+        lines.push("pinned: true".to_string());
+    }
+
+    if let Some(order) = frontmatter.sort_order {
+        lines.push(format!("sort_order: {}", order));
+    }
+
+    if let Some(ref source) = frontmatter.forked_from {
+        lines.push(format!("forked_from: \"{}\"", source));
+    }
     
     lines.join("\n")
 }
@@ -170,22 +238,64 @@ fn chrono_now() -> String {
     format!("{}Z", secs)
 }
 
-pub fn generate_timestamp() -> String {
+pub fn unix_timestamp() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
-    let duration = SystemTime::now()
+    SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .unwrap_or_default();
-    
-    let secs = duration.as_secs();
-    let days_since_epoch = secs / 86400;
-    let years = 1970 + (days_since_epoch / 365);
-    let remaining_days = days_since_epoch % 365;
-    let month = (remaining_days / 30) + 1;
-    let day = (remaining_days % 30) + 1;
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub fn generate_timestamp() -> String {
+    format_timestamp(unix_timestamp())
+}
+
+/// 与 `generate_timestamp` 相同的格式，便于和 frontmatter 中的时间按字符串比较
+pub fn format_timestamp(secs: u64) -> String {
     let hours = (secs % 86400) / 3600;
     let minutes = (secs % 3600) / 60;
     let seconds = secs % 60;
-    
-    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", 
-            years, month.min(12), day.min(31), hours, minutes, seconds)
+
+    format!("{}T{:02}:{:02}:{:02}Z", civil_date(secs / 86400), hours, minutes, seconds)
+}
+
+/// 自 1970-01-01 起第 `days` 天的公历日期（UTC），参见 Howard Hinnant 的 civil_from_days
+pub fn civil_date(days: u64) -> String {
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn generate_id() -> String {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    format!("{:x}-{:04x}", nanos, seq & 0xffff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_timestamps_as_utc_calendar_dates() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_735_689_599), "2024-12-31T23:59:59Z");
+        assert_eq!(civil_date(19_783), "2024-03-01");
+    }
 }
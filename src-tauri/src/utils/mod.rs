@@ -1,5 +1,23 @@
 pub mod error;
 pub mod markdown;
+pub mod diff;
+pub mod fs;
+pub mod template;
+pub mod deep_link;
+pub mod search;
+pub mod similarity;
+pub mod lint;
+pub mod i18n;
+pub mod crypto;
 
 pub use error::*;
 pub use markdown::*;
+pub use diff::*;
+pub use fs::*;
+pub use template::*;
+pub use deep_link::*;
+pub use search::*;
+pub use similarity::*;
+pub use lint::*;
+pub use i18n::*;
+pub use crypto::*;
@@ -0,0 +1,57 @@
+use crate::models::PromptItem;
+
+const TITLE_WEIGHT: f64 = 3.0;
+const TAG_WEIGHT: f64 = 2.0;
+const DESCRIPTION_WEIGHT: f64 = 1.5;
+const CONTENT_WEIGHT: f64 = 0.5;
+// 正文中重复出现的次数最多计入几次，避免长文本天然得分高
+const MAX_CONTENT_HITS: usize = 5;
+
+/// 标题、描述、标签或正文包含 `query`（不区分大小写）即视为匹配
+pub fn prompt_matches(prompt: &PromptItem, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return true;
+    }
+
+    prompt.title.to_lowercase().contains(&query)
+        || prompt.content.to_lowercase().contains(&query)
+        || prompt
+            .description
+            .as_ref()
+            .map(|d| d.to_lowercase().contains(&query))
+            .unwrap_or(false)
+        || prompt.tags.iter().any(|t| t.to_lowercase().contains(&query))
+}
+
+/// 按空白分词计算相关度；任意一个词都没有命中时返回 None
+pub fn relevance_score(prompt: &PromptItem, query: &str) -> Option<f64> {
+    let title = prompt.title.to_lowercase();
+    let description = prompt.description.as_deref().unwrap_or_default().to_lowercase();
+    let content = prompt.content.to_lowercase();
+    let tags: Vec<String> = prompt.tags.iter().map(|t| t.to_lowercase()).collect();
+
+    let mut score = 0.0;
+    for term in query.to_lowercase().split_whitespace() {
+        let mut term_score = 0.0;
+        if title.contains(term) {
+            term_score += if title.starts_with(term) { TITLE_WEIGHT * 1.5 } else { TITLE_WEIGHT };
+        }
+        if tags.iter().any(|t| t == term) {
+            term_score += TAG_WEIGHT;
+        } else if tags.iter().any(|t| t.contains(term)) {
+            term_score += TAG_WEIGHT / 2.0;
+        }
+        if description.contains(term) {
+            term_score += DESCRIPTION_WEIGHT;
+        }
+        term_score += content.matches(term).take(MAX_CONTENT_HITS).count() as f64 * CONTENT_WEIGHT;
+
+        if term_score == 0.0 {
+            return None;
+        }
+        score += term_score;
+    }
+
+    Some(score)
+}
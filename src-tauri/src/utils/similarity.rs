@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+/// 字符级 shingle 长度；按字符切分，中文无需分词也能比较
+const SHINGLE_SIZE: usize = 4;
+const NUM_HASHES: usize = 128;
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a(chars: impl IntoIterator<Item = char>) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for c in chars {
+        for byte in (c as u32).to_le_bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// 小写并合并空白，排除格式差异
+pub fn normalize_content(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// 归一化内容的哈希，用于判断完全重复
+pub fn content_hash(text: &str) -> u64 {
+    fnv1a(normalize_content(text).chars())
+}
+
+fn shingles(normalized: &str) -> HashSet<u64> {
+    let chars: Vec<char> = normalized.chars().collect();
+    if chars.len() <= SHINGLE_SIZE {
+        return HashSet::from([fnv1a(chars)]);
+    }
+    chars.windows(SHINGLE_SIZE).map(|w| fnv1a(w.iter().copied())).collect()
+}
+
+/// 计算内容的 MinHash 签名
+pub fn minhash_signature(text: &str) -> Vec<u64> {
+    let shingles = shingles(&normalize_content(text));
+    (0..NUM_HASHES as u64)
+        .map(|i| {
+            let seed = splitmix64(i);
+            shingles.iter().map(|s| splitmix64(s ^ seed)).min().unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+/// 两个签名相同位置相等的比例，即 Jaccard 相似度的估计值
+pub fn signature_similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    equal as f64 / a.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "You are a helpful assistant. Summarize the following article in three bullet points, \
+        keeping the original tone and citing any numbers exactly as written.";
+
+    #[test]
+    fn normalization_ignores_case_and_whitespace() {
+        assert_eq!(normalize_content("  Hello\n\tWORLD  "), "hello world");
+        assert_eq!(content_hash("Hello   World"), content_hash("hello world\n"));
+        assert_ne!(content_hash("hello world"), content_hash("hello words"));
+    }
+
+    #[test]
+    fn shingles_cover_short_and_long_text() {
+        // 不足一个 shingle 的内容整体作为一个 shingle
+        assert_eq!(shingles("abc").len(), 1);
+        assert_eq!(shingles("").len(), 1);
+        assert_eq!(shingles("abcdef").len(), 3);
+        // 重复窗口只计一次
+        assert_eq!(shingles("aaaaaa").len(), 1);
+        assert_eq!(shingles("你好世界你好").len(), 3);
+    }
+
+    #[test]
+    fn signatures_are_deterministic() {
+        let signature = minhash_signature(BASE);
+        assert_eq!(signature.len(), NUM_HASHES);
+        assert_eq!(signature, minhash_signature(BASE));
+        assert_eq!(signature_similarity(&signature, &minhash_signature(&BASE.to_uppercase())), 1.0);
+    }
+
+    #[test]
+    fn near_duplicates_pass_the_default_threshold() {
+        // 与 find_duplicate_prompts 的默认阈值 0.8 保持一致
+        let threshold = 0.8;
+        let base = minhash_signature(BASE);
+        let near = minhash_signature(&BASE.replace("three", "four"));
+        let unrelated = minhash_signature("Translate the text below into French and keep the markdown formatting.");
+
+        assert!(signature_similarity(&base, &near) >= threshold);
+        assert!(signature_similarity(&base, &near) < 1.0);
+        assert!(signature_similarity(&base, &unrelated) < 0.2);
+    }
+
+    #[test]
+    fn mismatched_signatures_are_not_similar() {
+        assert_eq!(signature_similarity(&[], &[]), 0.0);
+        assert_eq!(signature_similarity(&[1, 2], &[1]), 0.0);
+        assert_eq!(signature_similarity(&[1, 2, 3, 4], &[1, 2, 0, 0]), 0.5);
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+pub(crate) const PLACEHOLDER_OPEN: &str = "[[";
+pub(crate) const PLACEHOLDER_CLOSE: &str = "]]";
+
+/// 按出现顺序提取 `[[变量]]` 占位符（去重）
+pub fn extract_variables(content: &str) -> Vec<String> {
+    let mut variables: Vec<String> = vec![];
+    let mut rest = content;
+
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+        let Some(end) = after_open.find(PLACEHOLDER_CLOSE) else {
+            break;
+        };
+
+        let name = after_open[..end].trim();
+        if !name.is_empty() && !variables.iter().any(|v| v == name) {
+            variables.push(name.to_string());
+        }
+        rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+    }
+
+    variables
+}
+
+/// 内容中出现但 `variables` 未提供值的占位符
+pub fn missing_variables(content: &str, variables: &HashMap<String, String>) -> Vec<String> {
+    extract_variables(content)
+        .into_iter()
+        .filter(|name| !variables.contains_key(name))
+        .collect()
+}
+
+/// 用给定变量替换 `[[变量]]` 占位符；未提供值的占位符原样保留
+pub fn render_template(content: &str, variables: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+
+        let Some(end) = after_open.find(PLACEHOLDER_CLOSE) else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+
+        let name = after_open[..end].trim();
+        match variables.get(name) {
+            Some(value) => output.push_str(value),
+            None => output.push_str(&rest[start..start + PLACEHOLDER_OPEN.len() + end + PLACEHOLDER_CLOSE.len()]),
+        }
+        rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+    }
+
+    output.push_str(rest);
+    output
+}